@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use guardian_program::state::ComplianceStatus;
 
 declare_id!("3BxNMJ7VSjtVkrDLDY93D214MmQPBEx9LvTXu5gKxJkX");
 
@@ -29,9 +30,11 @@ pub struct Swap<'info> {
         ],
         bump = compliance.bump,
         seeds::program = guardian_program::id(),
-        constraint = compliance.is_whitelist @ ErrorCode::NotInWhitelist,
+        constraint = compliance.status == ComplianceStatus::Whitelisted @ ErrorCode::NotInWhitelist,
+        constraint = compliance.is_valid(clock.unix_timestamp) @ ErrorCode::NotInWhitelist,
     )]
     pub compliance: Box<Account<'info, guardian_program::state::Compliance>>,
+    pub clock: Sysvar<'info, Clock>,
     // swap accounts
     // ...
 }
\ No newline at end of file
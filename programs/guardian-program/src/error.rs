@@ -21,4 +21,88 @@ pub enum ZktGuardianError {
     MustBeProposalManager,
     #[msg("Unmatched access registry")]
     UnmatchedAccessRegistry,
-}
\ No newline at end of file
+    #[msg("Guardian already voted")]
+    AlreadyVoted,
+    #[msg("Proposal is still pending")]
+    ProposalStillPending,
+    #[msg("Invalid vote threshold")]
+    InvalidThreshold,
+    #[msg("Invalid voting period")]
+    InvalidVotingPeriod,
+    #[msg("Proposal is expired")]
+    ProposalExpired,
+    #[msg("Must be speaker or proposal manager")]
+    MustBeSpeakerOrProposalManager,
+    #[msg("Unmatched compliance account")]
+    UnmatchedComplianceAccount,
+    #[msg("Proposal targets are sealed")]
+    ProposalTargetsSealed,
+    #[msg("Proposal targets are in use")]
+    ProposalTargetsInUse,
+    #[msg("Unmatched proposal targets")]
+    UnmatchedProposalTargets,
+    #[msg("Unmatched execution cursor")]
+    UnmatchedExecutionCursor,
+    #[msg("Unmatched compliance payer")]
+    UnmatchedCompliancePayer,
+    #[msg("Unmatched access account")]
+    UnmatchedAccessAccount,
+    #[msg("Role governance is enabled")]
+    RoleGovernanceEnabled,
+    #[msg("Unmatched pending admin")]
+    UnmatchedPendingAdmin,
+    #[msg("Must be admin")]
+    MustBeAdmin,
+    #[msg("Not enough admin signers")]
+    NotEnoughAdminSigners,
+    #[msg("Invalid admin signers")]
+    InvalidAdminSigners,
+    #[msg("Role is expired")]
+    RoleExpired,
+    #[msg("Role is not expired")]
+    RoleNotExpired,
+    #[msg("Guardian was assigned after proposal creation")]
+    GuardianNotInSnapshot,
+    #[msg("Must be voter role")]
+    MustBeVoter,
+    #[msg("Invalid guardian weight")]
+    InvalidGuardianWeight,
+    #[msg("Invalid delegate")]
+    InvalidDelegate,
+    #[msg("Unmatched delegate")]
+    UnmatchedDelegate,
+    #[msg("Unmatched delegator accounts")]
+    UnmatchedDelegatorAccounts,
+    #[msg("Unmatched vote record")]
+    UnmatchedVoteRecord,
+    #[msg("Must be vetoer")]
+    MustBeVetoer,
+    #[msg("Only pending or approved proposal can be vetoed")]
+    ProposalNotVetoable,
+    #[msg("Proposal already executed")]
+    ProposalAlreadyExecuted,
+    #[msg("Veto reason too long")]
+    VetoReasonTooLong,
+    #[msg("Invalid execution delay")]
+    InvalidExecutionDelay,
+    #[msg("Execution delay not passed")]
+    ExecutionDelayNotPassed,
+    #[msg("Invalid execution period")]
+    InvalidExecutionPeriod,
+    #[msg("Execution deadline passed")]
+    ExecutionDeadlinePassed,
+    #[msg("Invalid valid until")]
+    InvalidValidUntil,
+    #[msg("Invalid risk score")]
+    InvalidRiskScore,
+    #[msg("Proposal not rejected")]
+    ProposalNotRejected,
+    #[msg("Unmatched access payer")]
+    UnmatchedAccessPayer,
+    #[msg("Account already migrated")]
+    AlreadyMigrated,
+    #[msg("Role governance disabled")]
+    RoleGovernanceDisabled,
+    #[msg("Vote delegated")]
+    VoteDelegated,
+}
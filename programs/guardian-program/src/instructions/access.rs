@@ -1,6 +1,10 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::clock::UnixTimestamp};
 
-use crate::state::{AccessRegistry, Access, Role};
+use crate::{
+    state::{AccessRegistry, Access, Role, Threshold},
+    error::ZktGuardianError,
+    instructions::realloc_if_needed,
+};
 
 #[derive(Accounts)]
 pub struct InitAccessRegistry<'info> {
@@ -18,6 +22,20 @@ pub struct InitAccessRegistry<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Checks that `admin` alone, or together with the signers in `remaining_accounts`, holds
+/// the admin authority of `access_registry`.
+pub(crate) fn check_admin(
+    access_registry: &AccessRegistry,
+    admin: &Signer,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    let signers: Vec<Pubkey> = std::iter::once(admin.key())
+        .chain(remaining_accounts.iter().filter(|account| account.is_signer).map(Key::key))
+        .collect();
+
+    access_registry.check_admin_signers(&signers)
+}
+
 pub(crate) fn _init_access_registry(ctx: Context<InitAccessRegistry>) -> Result<()> {
     ctx.accounts.access_global.admin = ctx.accounts.admin.key();
     ctx.accounts.access_global.bump = [ctx.bumps.access_global];
@@ -25,6 +43,76 @@ pub(crate) fn _init_access_registry(ctx: Context<InitAccessRegistry>) -> Result<
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct MigrateAccessRegistry<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: may still have the original layout, decoded in instruction after the realloc
+    #[account(mut, owner = crate::ID)]
+    pub access_registry: UncheckedAccount<'info>,
+    // system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a registry created with the original layout. Its zeroed tail decodes as the
+/// defaults of the appended fields, except for the guardian weight of its guardians.
+pub(crate) fn _migrate_access_registry(ctx: Context<MigrateAccessRegistry>) -> Result<()> {
+    let access_registry = ctx.accounts.access_registry.to_account_info();
+    require_gt!(
+        8 + AccessRegistry::SIZE,
+        access_registry.data_len(),
+        ZktGuardianError::AlreadyMigrated,
+    );
+    realloc_if_needed(
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        8 + AccessRegistry::SIZE,
+        &access_registry,
+    )?;
+
+    let mut registry = AccessRegistry::try_deserialize(&mut &access_registry.try_borrow_data()?[..])?;
+    registry.check_admin_signers(&[ctx.accounts.admin.key()])?;
+    registry.guardian_weight = registry.guardians as u64;
+    registry.try_serialize(&mut &mut access_registry.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccess<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub access_registry: Account<'info, AccessRegistry>,
+    /// CHECK: may still have the original layout, decoded in instruction after the realloc
+    #[account(mut, owner = crate::ID)]
+    pub access: UncheckedAccount<'info>,
+    // system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows an access account created with the original layout, so it decodes as a role
+/// without expiry. Roles assigned back then were paid by the admin, and guardians weigh 1
+/// as in the migrated registry.
+pub(crate) fn _migrate_access(ctx: Context<MigrateAccess>) -> Result<()> {
+    let access = ctx.accounts.access.to_account_info();
+    require_gt!(8 + Access::SIZE, access.data_len(), ZktGuardianError::AlreadyMigrated);
+    realloc_if_needed(&ctx.accounts.payer, &ctx.accounts.system_program, 8 + Access::SIZE, &access)?;
+
+    let mut data = Access::try_deserialize(&mut &access.try_borrow_data()?[..])?;
+    require_keys_eq!(
+        data.access_registry,
+        ctx.accounts.access_registry.key(),
+        ZktGuardianError::UnmatchedAccessRegistry,
+    );
+    data.payer = ctx.accounts.access_registry.admin;
+    if data.role == Role::Guardian {
+        data.weight = 1;
+    }
+    data.try_serialize(&mut &mut access.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 #[instruction(role: Role)]
 pub struct AssignRole<'info> {
@@ -32,7 +120,10 @@ pub struct AssignRole<'info> {
     pub admin: Signer<'info>,
     /// CHECK: no need to be checked
     pub owner: UncheckedAccount<'info>,
-    #[account(mut, has_one = admin)]
+    #[account(
+        mut,
+        constraint = !access_registry.role_governance @ ZktGuardianError::RoleGovernanceEnabled,
+    )]
     pub access_registry: Account<'info, AccessRegistry>,
     #[account(
         init,
@@ -51,12 +142,25 @@ pub struct AssignRole<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub(crate) fn _assign_role(ctx: Context<AssignRole>, role: Role) -> Result<()> {
-    ctx.accounts.access_registry.assign_role(role);
+pub(crate) fn _assign_role(
+    ctx: Context<AssignRole>,
+    role: Role,
+    expires_at: Option<UnixTimestamp>,
+    weight: u32,
+) -> Result<()> {
+    check_admin(&ctx.accounts.access_registry, &ctx.accounts.admin, ctx.remaining_accounts)?;
+    if role == Role::Guardian {
+        require_gt!(weight, 0, ZktGuardianError::InvalidGuardianWeight);
+    }
+    ctx.accounts.access_registry.assign_role(role, weight);
     
     ctx.accounts.access.access_registry = ctx.accounts.access_registry.key();
     ctx.accounts.access.role = role;
     ctx.accounts.access.owner = ctx.accounts.owner.key();
+    ctx.accounts.access.expires_at = expires_at;
+    ctx.accounts.access.epoch = ctx.accounts.access_registry.guardian_epoch;
+    ctx.accounts.access.weight = weight;
+    ctx.accounts.access.payer = ctx.accounts.admin.key();
 
     Ok(())
 }
@@ -64,14 +168,245 @@ pub(crate) fn _assign_role(ctx: Context<AssignRole>, role: Role) -> Result<()> {
 #[derive(Accounts)]
 pub struct RevokeRole<'info> {
     pub admin: Signer<'info>,
-    #[account(mut, has_one = admin)]
+    /// CHECK: receives the rent paid on assignment
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = !access_registry.role_governance @ ZktGuardianError::RoleGovernanceEnabled,
+    )]
     pub access_registry: Account<'info, AccessRegistry>,
-    #[account(mut, close = admin)]
+    #[account(mut, close = payer, has_one = access_registry, has_one = payer)]
     pub access: Account<'info, Access>,
 }
 
 pub(crate) fn _revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
-    ctx.accounts.access_registry.revoke_role(ctx.accounts.access.role);
+    check_admin(&ctx.accounts.access_registry, &ctx.accounts.admin, ctx.remaining_accounts)?;
+    ctx.accounts.access_registry.revoke_role(ctx.accounts.access.role, ctx.accounts.access.weight);
     
     Ok(())
 }
+
+#[derive(Accounts)]
+pub struct PruneExpiredRole<'info> {
+    /// CHECK: receives the rent paid on assignment
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub access_registry: Account<'info, AccessRegistry>,
+    #[account(
+        mut,
+        close = payer,
+        has_one = access_registry,
+        has_one = payer,
+        constraint = !access.is_active(clock.unix_timestamp) @ ZktGuardianError::RoleNotExpired,
+    )]
+    pub access: Account<'info, Access>,
+    // system programs
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub(crate) fn _prune_expired_role(ctx: Context<PruneExpiredRole>) -> Result<()> {
+    ctx.accounts.access_registry.revoke_role(ctx.accounts.access.role, ctx.accounts.access.weight);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateGuardianWeight<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        constraint = !access_registry.role_governance @ ZktGuardianError::RoleGovernanceEnabled,
+    )]
+    pub access_registry: Account<'info, AccessRegistry>,
+    #[account(
+        mut,
+        has_one = access_registry,
+        constraint = access.role == Role::Guardian @ ZktGuardianError::MustBeGuardian,
+    )]
+    pub access: Account<'info, Access>,
+}
+
+/// Changes the weight of a guardian. Like any guardian change this starts a new guardian
+/// epoch, so the guardian cannot vote at all on proposals already in flight, while those
+/// proposals keep its old weight in their snapshot of the total guardian weight. Once role
+/// governance is enabled, weights are changed through `SetGuardianWeight` proposals.
+pub(crate) fn _update_guardian_weight(ctx: Context<UpdateGuardianWeight>, weight: u32) -> Result<()> {
+    check_admin(&ctx.accounts.access_registry, &ctx.accounts.admin, ctx.remaining_accounts)?;
+    ctx.accounts.access_registry.update_guardian_weight(&mut ctx.accounts.access, weight)
+}
+
+#[derive(Accounts)]
+pub struct DelegateVote<'info> {
+    pub guardian: Signer<'info>,
+    pub access_registry: Account<'info, AccessRegistry>,
+    #[account(
+        mut,
+        has_one = access_registry,
+        constraint = access.owner == guardian.key(),
+        constraint = access.role == Role::Guardian @ ZktGuardianError::MustBeGuardian,
+        constraint = access.is_active(clock.unix_timestamp) @ ZktGuardianError::RoleExpired,
+    )]
+    pub access: Account<'info, Access>,
+    #[account(
+        has_one = access_registry,
+        constraint = delegate_access.owner != guardian.key() @ ZktGuardianError::InvalidDelegate,
+        constraint = delegate_access.role == Role::Guardian @ ZktGuardianError::MustBeGuardian,
+        constraint = delegate_access.is_active(clock.unix_timestamp) @ ZktGuardianError::RoleExpired,
+        // a guardian that delegated can't vote, so delegated votes are never forwarded further
+        constraint = delegate_access.delegate.is_none() @ ZktGuardianError::InvalidDelegate,
+    )]
+    pub delegate_access: Account<'info, Access>,
+    // system programs
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub(crate) fn _delegate_vote(ctx: Context<DelegateVote>) -> Result<()> {
+    ctx.accounts.access.delegate = Some(ctx.accounts.delegate_access.owner);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UndelegateVote<'info> {
+    pub guardian: Signer<'info>,
+    #[account(mut, constraint = access.owner == guardian.key())]
+    pub access: Account<'info, Access>,
+}
+
+pub(crate) fn _undelegate_vote(ctx: Context<UndelegateVote>) -> Result<()> {
+    ctx.accounts.access.delegate = None;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetVoteThreshold<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub access_registry: Account<'info, AccessRegistry>,
+}
+
+pub(crate) fn _set_vote_threshold(
+    ctx: Context<SetVoteThreshold>,
+    quorum: u32,
+    threshold: Threshold,
+) -> Result<()> {
+    check_admin(&ctx.accounts.access_registry, &ctx.accounts.admin, ctx.remaining_accounts)?;
+    ctx.accounts.access_registry.set_threshold(quorum, threshold)
+}
+
+#[derive(Accounts)]
+pub struct SetMinCommunitySupport<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub access_registry: Account<'info, AccessRegistry>,
+}
+
+pub(crate) fn _set_min_community_support(
+    ctx: Context<SetMinCommunitySupport>,
+    min_community_support: u32,
+) -> Result<()> {
+    check_admin(&ctx.accounts.access_registry, &ctx.accounts.admin, ctx.remaining_accounts)?;
+    ctx.accounts.access_registry.min_community_support = min_community_support;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct EnableRoleGovernance<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub access_registry: Account<'info, AccessRegistry>,
+}
+
+/// Hands role assignment over to guardian voting, this cannot be undone by the admin.
+pub(crate) fn _enable_role_governance(ctx: Context<EnableRoleGovernance>) -> Result<()> {
+    check_admin(&ctx.accounts.access_registry, &ctx.accounts.admin, ctx.remaining_accounts)?;
+    ctx.accounts.access_registry.role_governance = true;
+
+    Ok(())
+}
+
+#[event]
+pub struct ProposeAdminEvent {
+    pub access_registry: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+    /// CHECK: no need to be checked
+    pub pending_admin: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub access_registry: Account<'info, AccessRegistry>,
+}
+
+pub(crate) fn _propose_admin(ctx: Context<ProposeAdmin>) -> Result<()> {
+    check_admin(&ctx.accounts.access_registry, &ctx.accounts.admin, ctx.remaining_accounts)?;
+    ctx.accounts.access_registry.pending_admin = Some(ctx.accounts.pending_admin.key());
+
+    emit_cpi!(ProposeAdminEvent {
+        access_registry: ctx.accounts.access_registry.key(),
+        admin: ctx.accounts.admin.key(),
+        pending_admin: ctx.accounts.pending_admin.key(),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct AcceptAdminEvent {
+    pub access_registry: Pubkey,
+    pub previous_admin: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+    #[account(
+        mut,
+        constraint = access_registry.pending_admin == Some(pending_admin.key()) @ ZktGuardianError::UnmatchedPendingAdmin,
+    )]
+    pub access_registry: Account<'info, AccessRegistry>,
+}
+
+/// Moves admin authority to the pending admin, the registry keeps the address derived
+/// from the initial admin.
+pub(crate) fn _accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let previous_admin = ctx.accounts.access_registry.admin;
+    ctx.accounts.access_registry.admin = ctx.accounts.pending_admin.key();
+    ctx.accounts.access_registry.pending_admin = None;
+
+    emit_cpi!(AcceptAdminEvent {
+        access_registry: ctx.accounts.access_registry.key(),
+        previous_admin,
+        admin: ctx.accounts.pending_admin.key(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetAdminSigners<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub access_registry: Account<'info, AccessRegistry>,
+    // remaining accounts: further admin signers
+}
+
+/// Replaces the admin signer set, authorized by the current admin authority.
+pub(crate) fn _set_admin_signers(
+    ctx: Context<SetAdminSigners>,
+    admin_signers: Vec<Pubkey>,
+    admin_threshold: u8,
+) -> Result<()> {
+    check_admin(&ctx.accounts.access_registry, &ctx.accounts.admin, ctx.remaining_accounts)?;
+    ctx.accounts.access_registry.set_admin_signers(admin_signers, admin_threshold)
+}
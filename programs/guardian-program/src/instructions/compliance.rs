@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    state::{Access, AccessRegistry, Role, ComplianceRegistry, Compliance},
+    state::{Access, AccessRegistry, Role, ComplianceRegistry, Compliance, CATEGORY_COUNT},
     error::ZktGuardianError,
+    instructions::realloc_if_needed,
 };
 
 #[derive(Accounts)]
@@ -27,6 +28,71 @@ pub(crate) fn _init_compliance_registry(ctx: Context<InitComplianceRegistry>) ->
     ctx.accounts.compliance_registry.access_registry = ctx.accounts.access_registry.key();
     ctx.accounts.compliance_registry.whitelists = 0;
     ctx.accounts.compliance_registry.blacklists = 0;
+    ctx.accounts.compliance_registry.categories = [0; CATEGORY_COUNT];
+    ctx.accounts.compliance_registry.watchlists = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateComplianceRegistry<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: may still have the original layout, decoded in instruction after the realloc
+    #[account(mut, owner = crate::ID)]
+    pub compliance_registry: UncheckedAccount<'info>,
+    // system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a registry created with the original layout. Entries written back then carry no
+/// categories, so the zeroed counters appended since are already correct.
+pub(crate) fn _migrate_compliance_registry(ctx: Context<MigrateComplianceRegistry>) -> Result<()> {
+    let compliance_registry = ctx.accounts.compliance_registry.to_account_info();
+    require_gt!(
+        8 + ComplianceRegistry::SIZE,
+        compliance_registry.data_len(),
+        ZktGuardianError::AlreadyMigrated,
+    );
+    realloc_if_needed(
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        8 + ComplianceRegistry::SIZE,
+        &compliance_registry,
+    )?;
+    ComplianceRegistry::try_deserialize(&mut &compliance_registry.try_borrow_data()?[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateCompliance<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: may still have the original layout, decoded in instruction after the realloc
+    #[account(mut, owner = crate::ID)]
+    pub compliance: UncheckedAccount<'info>,
+    // system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows an entry created with the original layout. Entries whose description filled the
+/// original space have no room left for the fields appended since and can't be decoded,
+/// so reading them through `utils` or `revoke_compliance` needs this first.
+pub(crate) fn _migrate_compliance(ctx: Context<MigrateCompliance>) -> Result<()> {
+    let compliance = ctx.accounts.compliance.to_account_info();
+    require_gt!(
+        8 + Compliance::SIZE,
+        compliance.data_len(),
+        ZktGuardianError::AlreadyMigrated,
+    );
+    realloc_if_needed(
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        8 + Compliance::SIZE,
+        &compliance,
+    )?;
+    Compliance::try_deserialize(&mut &compliance.try_borrow_data()?[..])?;
 
     Ok(())
 }
@@ -43,6 +109,7 @@ pub struct RevokeCompliance<'info> {
         has_one = access_registry,
         constraint = access.owner == compliance_manager.key(),
         constraint = access.role == Role::ProposalManager @ ZktGuardianError::MustBeProposalManager,
+        constraint = access.is_active(clock.unix_timestamp) @ ZktGuardianError::RoleExpired,
     )]
     pub access: Account<'info, Access>,
     #[account(mut, has_one = access_registry)]
@@ -59,10 +126,15 @@ pub struct RevokeCompliance<'info> {
         bump = compliance.bump,
     )]
     pub compliance: Account<'info, Compliance>,
+    // system programs
+    pub clock: Sysvar<'info, Clock>,
 }
 
 pub(crate) fn _revoke_compliance(ctx: Context<RevokeCompliance>) -> Result<()> {
-    ctx.accounts.compliance_registry.revoke(ctx.accounts.compliance.is_whitelist);
+    ctx.accounts.compliance_registry.revoke(
+        ctx.accounts.compliance.status,
+        ctx.accounts.compliance.categories,
+    );
 
     Ok(())
 }
\ No newline at end of file
@@ -1,12 +1,15 @@
-use anchor_lang::{prelude::*, solana_program::clock::UnixTimestamp, Key};
+use anchor_lang::{prelude::*, solana_program::clock::UnixTimestamp, system_program, Key};
 
 use crate::{
     state::{
         AccessRegistry, Access, Role,
-        ProposalRegistry, Proposal, ProposalStatus,
-        ComplianceRegistry, Compliance,
+        ProposalRegistry, Proposal, ProposalKind, ProposalStatus, ProposalTargets, VoteRecord, VoteChoice,
+        SignalRecord,
+        DEFAULT_VOTING_PERIOD, DEFAULT_EXECUTION_PERIOD,
+        ComplianceRegistry, Compliance, ComplianceStatus,
     },
     error::ZktGuardianError,
+    instructions::check_admin,
 };
 
 #[derive(Accounts)]
@@ -32,6 +35,166 @@ pub(crate) fn _init_proposal_registry(ctx: Context<InitProposalRegistry>) -> Res
     ctx.accounts.proposal_registry.pending = 0;
     ctx.accounts.proposal_registry.approved = 0;
     ctx.accounts.proposal_registry.rejected = 0;
+    ctx.accounts.proposal_registry.cancelled = 0;
+    ctx.accounts.proposal_registry.expired = 0;
+    ctx.accounts.proposal_registry.voting_period = DEFAULT_VOTING_PERIOD;
+    ctx.accounts.proposal_registry.execution_delay = 0;
+    ctx.accounts.proposal_registry.blacklist_exempt = false;
+    ctx.accounts.proposal_registry.execution_period = DEFAULT_EXECUTION_PERIOD;
+    ctx.accounts.proposal_registry.next_proposal_id = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateProposalRegistry<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: may still have the original layout, decoded in instruction after the realloc
+    #[account(mut, owner = crate::ID)]
+    pub proposal_registry: UncheckedAccount<'info>,
+    // system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a registry created with the original layout and fills in the default periods.
+///
+/// Proposals created before the migration keep their original layout, which no longer
+/// decodes. They can be neither voted on nor closed, so their rent stays locked and they
+/// remain counted in `pending`.
+pub(crate) fn _migrate_proposal_registry(ctx: Context<MigrateProposalRegistry>) -> Result<()> {
+    let proposal_registry = ctx.accounts.proposal_registry.to_account_info();
+    require_gt!(
+        8 + ProposalRegistry::SIZE,
+        proposal_registry.data_len(),
+        ZktGuardianError::AlreadyMigrated,
+    );
+    realloc_if_needed(
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        8 + ProposalRegistry::SIZE,
+        &proposal_registry,
+    )?;
+
+    let mut registry = ProposalRegistry::try_deserialize(&mut &proposal_registry.try_borrow_data()?[..])?;
+    registry.migrate();
+    registry.try_serialize(&mut &mut proposal_registry.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetVotingPeriod<'info> {
+    pub admin: Signer<'info>,
+    pub access_registry: Account<'info, AccessRegistry>,
+    #[account(mut, has_one = access_registry)]
+    pub proposal_registry: Account<'info, ProposalRegistry>,
+}
+
+pub(crate) fn _set_voting_period(ctx: Context<SetVotingPeriod>, voting_period: i64) -> Result<()> {
+    check_admin(&ctx.accounts.access_registry, &ctx.accounts.admin, ctx.remaining_accounts)?;
+    ctx.accounts.proposal_registry.set_voting_period(voting_period)
+}
+
+#[derive(Accounts)]
+pub struct SetExecutionDelay<'info> {
+    pub admin: Signer<'info>,
+    pub access_registry: Account<'info, AccessRegistry>,
+    #[account(mut, has_one = access_registry)]
+    pub proposal_registry: Account<'info, ProposalRegistry>,
+}
+
+pub(crate) fn _set_execution_delay(
+    ctx: Context<SetExecutionDelay>,
+    execution_delay: i64,
+    blacklist_exempt: bool,
+) -> Result<()> {
+    check_admin(&ctx.accounts.access_registry, &ctx.accounts.admin, ctx.remaining_accounts)?;
+    ctx.accounts.proposal_registry.set_execution_delay(execution_delay, blacklist_exempt)
+}
+
+#[derive(Accounts)]
+pub struct SetExecutionPeriod<'info> {
+    pub admin: Signer<'info>,
+    pub access_registry: Account<'info, AccessRegistry>,
+    #[account(mut, has_one = access_registry)]
+    pub proposal_registry: Account<'info, ProposalRegistry>,
+}
+
+pub(crate) fn _set_execution_period(ctx: Context<SetExecutionPeriod>, execution_period: i64) -> Result<()> {
+    check_admin(&ctx.accounts.access_registry, &ctx.accounts.admin, ctx.remaining_accounts)?;
+    ctx.accounts.proposal_registry.set_execution_period(execution_period)
+}
+
+#[derive(Accounts)]
+#[instruction(target_accounts: Vec<Pubkey>)]
+pub struct InitProposalTargets<'info> {
+    #[account(mut)]
+    pub speaker: Signer<'info>,
+    #[account(
+        init,
+        payer = speaker,
+        space = 8 + ProposalTargets::space(target_accounts.len()),
+    )]
+    pub proposal_targets: Account<'info, ProposalTargets>,
+    // system program
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn _init_proposal_targets(
+    ctx: Context<InitProposalTargets>,
+    target_accounts: Vec<Pubkey>,
+) -> Result<()> {
+    ctx.accounts.proposal_targets.speaker = ctx.accounts.speaker.key();
+    ctx.accounts.proposal_targets.target_accounts = target_accounts;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(target_accounts: Vec<Pubkey>)]
+pub struct AppendProposalTargets<'info> {
+    #[account(mut)]
+    pub speaker: Signer<'info>,
+    #[account(
+        mut,
+        has_one = speaker,
+        constraint = proposal_targets.proposal == Pubkey::default() @ ZktGuardianError::ProposalTargetsSealed,
+        realloc = 8 + ProposalTargets::space(proposal_targets.target_accounts.len() + target_accounts.len()),
+        realloc::payer = speaker,
+        realloc::zero = false,
+    )]
+    pub proposal_targets: Account<'info, ProposalTargets>,
+    // system program
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn _append_proposal_targets(
+    ctx: Context<AppendProposalTargets>,
+    target_accounts: Vec<Pubkey>,
+) -> Result<()> {
+    ctx.accounts.proposal_targets.target_accounts.extend(target_accounts);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseProposalTargets<'info> {
+    #[account(mut)]
+    pub speaker: Signer<'info>,
+    /// CHECK: may not exist or already be closed, checked in instruction
+    pub proposal: UncheckedAccount<'info>,
+    #[account(mut, close = speaker, has_one = speaker, has_one = proposal)]
+    pub proposal_targets: Account<'info, ProposalTargets>,
+}
+
+pub(crate) fn _close_proposal_targets(ctx: Context<CloseProposalTargets>) -> Result<()> {
+    let proposal = ctx.accounts.proposal.to_account_info();
+    // unbound lists point at the default key, which is never owned by this program
+    if proposal.owner == &crate::ID {
+        let proposal = Proposal::try_deserialize(&mut &proposal.try_borrow_data()?[..])?;
+        require_eq!(proposal.status, ProposalStatus::Rejected, ZktGuardianError::ProposalTargetsInUse);
+    }
 
     Ok(())
 }
@@ -42,14 +205,17 @@ pub struct CreateProposalEvent {
     pub access_registry: Pubkey,
     pub proposal_registry: Pubkey,
     pub proposal: Pubkey,
-    pub is_whitelist: bool,
+    pub id: u64,
+    pub kind: ProposalKind,
+    pub compliance_status: ComplianceStatus,
     pub timestamp: UnixTimestamp,
     pub guardians: u32,
+    pub guardian_weight: u64,
 }
 
 #[event_cpi]
 #[derive(Accounts)]
-#[instruction(description: String, is_whitelist: bool)]
+#[instruction(description: String, compliance_status: ComplianceStatus, kind: ProposalKind)]
 pub struct CreateProposal<'info> {
     #[account(mut)]
     pub speaker: Signer<'info>,
@@ -58,6 +224,7 @@ pub struct CreateProposal<'info> {
         has_one = access_registry,
         constraint = access.owner == speaker.key(),
         constraint = access.role == Role::Speaker @ ZktGuardianError::MustBeSpeaker,
+        constraint = access.is_active(clock.unix_timestamp) @ ZktGuardianError::RoleExpired,
     )]
     pub access: Account<'info, Access>,
     #[account(mut, has_one = access_registry)]
@@ -66,8 +233,17 @@ pub struct CreateProposal<'info> {
         init,
         payer = speaker,
         space = 8 + Proposal::SIZE,
+        seeds = [
+            b"proposal",
+            proposal_registry.key().as_ref(),
+            proposal_registry.next_proposal_id.to_le_bytes().as_ref(),
+        ],
+        bump,
     )]
     pub proposal: Box<Account<'info, Proposal>>,
+    // replaces the target accounts in remaining accounts for large proposals
+    #[account(mut, has_one = speaker)]
+    pub proposal_targets: Option<Account<'info, ProposalTargets>>,
     // system program
     pub clock: Sysvar<'info, Clock>,
     pub system_program: Program<'info, System>,
@@ -76,19 +252,34 @@ pub struct CreateProposal<'info> {
 pub(crate) fn _create_proposal(
     ctx: Context<CreateProposal>,
     description: String,
-    is_whitelist: bool,
+    compliance_status: ComplianceStatus,
+    kind: ProposalKind,
+    valid_until: Option<UnixTimestamp>,
+    risk_score: u8,
+    categories: u8,
 ) -> Result<()> {
     let proposal_registry_key = ctx.accounts.proposal_registry.key();
-    let proposal = Proposal::new(
+    let mut proposal = Proposal::new(
         proposal_registry_key,
         &mut ctx.accounts.proposal_registry,
         ctx.accounts.speaker.key(),
         ctx.remaining_accounts.iter().map(Key::key).collect(),
-        is_whitelist,
+        kind,
+        compliance_status,
+        valid_until,
+        risk_score,
+        categories,
         description,
         &ctx.accounts.clock,
-        ctx.accounts.access_registry.guardians,
+        &ctx.accounts.access_registry,
     )?;
+    if let Some(proposal_targets) = ctx.accounts.proposal_targets.as_mut() {
+        proposal.bind_targets(
+            ctx.accounts.proposal.key(),
+            proposal_targets.key(),
+            proposal_targets,
+        )?;
+    }
     ctx.accounts.proposal.set_inner(proposal);
     
     emit_cpi!(CreateProposalEvent {
@@ -96,9 +287,12 @@ pub(crate) fn _create_proposal(
         access_registry: ctx.accounts.access_registry.key(),
         proposal_registry: proposal_registry_key,
         proposal: ctx.accounts.proposal.key(),
-        is_whitelist,
+        id: ctx.accounts.proposal.id,
+        kind,
+        compliance_status,
         timestamp: ctx.accounts.clock.unix_timestamp,
         guardians: ctx.accounts.access_registry.guardians,
+        guardian_weight: ctx.accounts.access_registry.guardian_weight,
     });
 
     Ok(())
@@ -106,27 +300,335 @@ pub(crate) fn _create_proposal(
 
 #[derive(Accounts)]
 pub struct VoteForProposal<'info> {
+    #[account(mut)]
     pub guardian: Signer<'info>,
     pub access_registry: Account<'info, AccessRegistry>,
     #[account(
         has_one = access_registry,
         constraint = access.owner == guardian.key(),
         constraint = access.role == Role::Guardian @ ZktGuardianError::MustBeGuardian,
+        constraint = access.is_active(clock.unix_timestamp) @ ZktGuardianError::RoleExpired,
+        constraint = access.delegate.is_none() @ ZktGuardianError::VoteDelegated,
+    )]
+    access: Account<'info, Access>,
+    #[account(mut, has_one = access_registry)]
+    pub proposal_registry: Account<'info, ProposalRegistry>,
+    #[account(
+        mut,
+        has_one = proposal_registry,
+        constraint = access.epoch <= proposal.guardian_epoch @ ZktGuardianError::GuardianNotInSnapshot,
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    #[account(
+        init_if_needed,
+        payer = guardian,
+        space = 8 + VoteRecord::SIZE,
+        seeds = [b"vote", proposal.key().as_ref(), guardian.key().as_ref()],
+        bump,
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    // system program
+    pub clock: Sysvar<'info, Clock>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts: (access, vote record) pairs of guardians delegating to this guardian
+}
+
+/// Casts the guardian's vote together with the votes delegated to it. Delegators are passed
+/// in remaining accounts as (access, vote record) pairs, every underlying guardian gets its
+/// own vote record so a vote can never be counted twice.
+pub(crate) fn _vote_for_proposal<'info>(
+    ctx: Context<'_, '_, '_, 'info, VoteForProposal<'info>>,
+    choice: VoteChoice,
+) -> Result<()> {
+    let proposal_key = ctx.accounts.proposal.key();
+    let guardian_key = ctx.accounts.guardian.key();
+    let mut weight = 0;
+
+    // a fresh vote record is zeroed, a used one always points at its proposal
+    if ctx.accounts.vote_record.proposal == Pubkey::default() {
+        let vote_record = VoteRecord::new(
+            ctx.bumps.vote_record,
+            proposal_key,
+            guardian_key,
+            guardian_key,
+            choice,
+        );
+        ctx.accounts.vote_record.set_inner(vote_record);
+        weight += ctx.accounts.access.weight as u64;
+    } else {
+        // the guardian already voted for itself and only casts delegated votes now
+        require!(!ctx.remaining_accounts.is_empty(), ZktGuardianError::AlreadyVoted);
+    }
+
+    let delegators = check_delegators(
+        ctx.program_id,
+        &ctx.accounts.access_registry.key(),
+        &proposal_key,
+        ctx.accounts.proposal.guardian_epoch,
+        &guardian_key,
+        ctx.accounts.clock.unix_timestamp,
+        ctx.remaining_accounts,
+    )?;
+    for (accounts, (delegator, bump)) in ctx.remaining_accounts.chunks_exact(2).zip(delegators) {
+        let delegator_vote_record = &accounts[1];
+        let seeds = [b"vote".as_ref(), proposal_key.as_ref(), delegator.owner.as_ref()];
+        init_pda_if_needed(
+            ctx.program_id,
+            &ctx.accounts.guardian,
+            &ctx.accounts.system_program,
+            &[&seeds[..], &[&[bump]]].concat(),
+            8 + VoteRecord::SIZE,
+            delegator_vote_record,
+        )?;
+        VoteRecord::new(bump, proposal_key, delegator.owner, guardian_key, choice)
+            .try_serialize(&mut &mut delegator_vote_record.try_borrow_mut_data()?[..])?;
+        weight += delegator.weight as u64;
+    }
+
+    ctx.accounts.proposal.vote(
+        choice,
+        weight,
+        &ctx.accounts.clock,
+        &mut ctx.accounts.proposal_registry,
+    )
+}
+
+/// Checks the (access, vote record) pairs of guardians delegating to `guardian` and returns
+/// every delegator with the bump of its vote record. A delegator may appear only once and
+/// must not have voted on the proposal yet.
+fn check_delegators(
+    program_id: &Pubkey,
+    access_registry: &Pubkey,
+    proposal: &Pubkey,
+    guardian_epoch: u64,
+    guardian: &Pubkey,
+    now: UnixTimestamp,
+    accounts: &[AccountInfo],
+) -> Result<Vec<(Access, u8)>> {
+    let pairs = accounts.chunks_exact(2);
+    require!(pairs.remainder().is_empty(), ZktGuardianError::UnmatchedDelegatorAccounts);
+
+    let mut delegators: Vec<(Access, u8)> = Vec::with_capacity(pairs.len());
+    for accounts in pairs {
+        let (delegator_access, delegator_vote_record) = (&accounts[0], &accounts[1]);
+        require_keys_eq!(
+            *delegator_access.owner,
+            *program_id,
+            ZktGuardianError::UnmatchedAccessAccount,
+        );
+        let delegator = Access::try_deserialize(&mut &delegator_access.try_borrow_data()?[..])?;
+        require_keys_eq!(
+            delegator.access_registry,
+            *access_registry,
+            ZktGuardianError::UnmatchedAccessRegistry,
+        );
+        require!(delegator.role == Role::Guardian, ZktGuardianError::MustBeGuardian);
+        require!(delegator.is_active(now), ZktGuardianError::RoleExpired);
+        require!(delegator.delegate == Some(*guardian), ZktGuardianError::UnmatchedDelegate);
+        require_gte!(
+            guardian_epoch,
+            delegator.epoch,
+            ZktGuardianError::GuardianNotInSnapshot,
+        );
+
+        let seeds = [b"vote".as_ref(), proposal.as_ref(), delegator.owner.as_ref()];
+        let bump = find_bump(
+            program_id,
+            &seeds,
+            delegator_vote_record,
+            ZktGuardianError::UnmatchedVoteRecord,
+        )?;
+        require_keys_neq!(
+            *delegator_vote_record.owner,
+            *program_id,
+            ZktGuardianError::AlreadyVoted,
+        );
+        require!(
+            delegators.iter().all(|(other, _)| other.owner != delegator.owner),
+            ZktGuardianError::AlreadyVoted,
+        );
+        delegators.push((delegator, bump));
+    }
+
+    Ok(delegators)
+}
+
+#[derive(Accounts)]
+pub struct SignalProposal<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub access_registry: Account<'info, AccessRegistry>,
+    #[account(
+        has_one = access_registry,
+        constraint = access.owner == voter.key(),
+        constraint = access.role == Role::Voter @ ZktGuardianError::MustBeVoter,
+        constraint = access.is_active(clock.unix_timestamp) @ ZktGuardianError::RoleExpired,
     )]
     access: Account<'info, Access>,
     #[account(mut, has_one = access_registry)]
     pub proposal_registry: Account<'info, ProposalRegistry>,
     #[account(mut, has_one = proposal_registry)]
     pub proposal: Box<Account<'info, Proposal>>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + SignalRecord::SIZE,
+        seeds = [b"signal", proposal.key().as_ref(), voter.key().as_ref()],
+        bump,
+    )]
+    pub signal_record: Account<'info, SignalRecord>,
+    // system program
+    pub clock: Sysvar<'info, Clock>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn _signal_proposal(ctx: Context<SignalProposal>, support: bool) -> Result<()> {
+    require_keys_eq!(
+        ctx.accounts.signal_record.proposal,
+        Pubkey::default(),
+        ZktGuardianError::AlreadyVoted,
+    );
+    let signal_record = SignalRecord::new(
+        ctx.bumps.signal_record,
+        ctx.accounts.proposal.key(),
+        ctx.accounts.voter.key(),
+        support,
+    );
+    ctx.accounts.signal_record.set_inner(signal_record);
+
+    ctx.accounts.proposal.signal(
+        support,
+        &ctx.accounts.clock,
+        &mut ctx.accounts.proposal_registry,
+    )
+}
+
+#[derive(Accounts)]
+pub struct CloseSignalRecord<'info> {
+    /// CHECK: no need to be checked
+    #[account(mut)]
+    pub voter: UncheckedAccount<'info>,
+    /// CHECK: may already be closed, checked in instruction
+    pub proposal: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = voter,
+        has_one = voter,
+        has_one = proposal,
+        seeds = [b"signal", proposal.key().as_ref(), voter.key().as_ref()],
+        bump = signal_record.bump,
+    )]
+    pub signal_record: Account<'info, SignalRecord>,
+}
+
+pub(crate) fn _close_signal_record(ctx: Context<CloseSignalRecord>) -> Result<()> {
+    let proposal = ctx.accounts.proposal.to_account_info();
+    // a closed proposal is handed back to the system program
+    if proposal.owner == &crate::ID {
+        let proposal = Proposal::try_deserialize(&mut &proposal.try_borrow_data()?[..])?;
+        require_neq!(proposal.status, ProposalStatus::Pending, ZktGuardianError::ProposalStillPending);
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseVoteRecord<'info> {
+    /// CHECK: no need to be checked
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    /// CHECK: may already be closed, checked in instruction
+    pub proposal: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        has_one = proposal,
+        seeds = [b"vote", proposal.key().as_ref(), vote_record.guardian.as_ref()],
+        bump = vote_record.bump,
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+}
+
+pub(crate) fn _close_vote_record(ctx: Context<CloseVoteRecord>) -> Result<()> {
+    let proposal = ctx.accounts.proposal.to_account_info();
+    // a closed proposal is handed back to the system program
+    if proposal.owner == &crate::ID {
+        let proposal = Proposal::try_deserialize(&mut &proposal.try_borrow_data()?[..])?;
+        require_neq!(proposal.status, ProposalStatus::Pending, ZktGuardianError::ProposalStillPending);
+    }
+
+    Ok(())
 }
 
-pub(crate) fn _vote_for_proposal(ctx: Context<VoteForProposal>) -> Result<()> {
-    ctx.accounts.proposal.vote(&mut ctx.accounts.proposal_registry)
+#[event]
+pub struct ProposalRejectedEvent {
+    pub speaker: Pubkey,
+    pub proposal_registry: Pubkey,
+    pub proposal: Pubkey,
+    pub timestamp: UnixTimestamp,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct RejectExpiredProposal<'info> {
+    #[account(mut)]
+    pub proposal_registry: Account<'info, ProposalRegistry>,
+    #[account(mut, has_one = proposal_registry)]
+    pub proposal: Box<Account<'info, Proposal>>,
+    // system programs
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub(crate) fn _reject_expired_proposal(ctx: Context<RejectExpiredProposal>) -> Result<()> {
+    ctx.accounts.proposal.reject_expired(
+        &ctx.accounts.clock,
+        &mut ctx.accounts.proposal_registry,
+    )?;
+
+    emit_cpi!(ProposalRejectedEvent {
+        speaker: ctx.accounts.proposal.speaker,
+        proposal_registry: ctx.accounts.proposal_registry.key(),
+        proposal: ctx.accounts.proposal.key(),
+        timestamp: ctx.accounts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseRejectedProposal<'info> {
     /// CHECK: no need to be checked
+    #[account(mut)]
+    pub speaker: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = speaker,
+        has_one = speaker,
+        constraint = proposal.status == ProposalStatus::Rejected @ ZktGuardianError::ProposalNotRejected,
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+}
+
+/// Returns the rent of a rejected proposal to its speaker. The rejection is already recorded
+/// in the registry counters and events, so anyone can close it.
+pub(crate) fn _close_rejected_proposal(_ctx: Context<CloseRejectedProposal>) -> Result<()> {
+    Ok(())
+}
+
+#[event]
+pub struct ProposalExpiredEvent {
+    pub speaker: Pubkey,
+    pub proposal_registry: Pubkey,
+    pub proposal: Pubkey,
+    pub timestamp: UnixTimestamp,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExpireProposal<'info> {
+    /// CHECK: no need to be checked
+    #[account(mut)]
     pub speaker: UncheckedAccount<'info>,
     #[account(mut)]
     pub proposal_registry: Account<'info, ProposalRegistry>,
@@ -136,93 +638,659 @@ pub struct RejectExpiredProposal<'info> {
     pub clock: Sysvar<'info, Clock>,
 }
 
-pub(crate) fn _reject_expired_proposal(ctx: Context<RejectExpiredProposal>) -> Result<()> {
-    ctx.accounts.proposal.reject_expired(
+pub(crate) fn _expire_proposal(ctx: Context<ExpireProposal>) -> Result<()> {
+    ctx.accounts.proposal.expire(
         &ctx.accounts.clock,
         &mut ctx.accounts.proposal_registry,
-    )
+    )?;
+
+    emit_cpi!(ProposalExpiredEvent {
+        speaker: ctx.accounts.speaker.key(),
+        proposal_registry: ctx.accounts.proposal_registry.key(),
+        proposal: ctx.accounts.proposal.key(),
+        timestamp: ctx.accounts.clock.unix_timestamp,
+    });
+
+    Ok(())
 }
 
-macro_rules! impl_execute_proposal {
-    ($num:tt, [$($idx:tt),+]) => {
-        paste::paste! {
-            #[derive(Accounts)]
-            pub struct [<ExecuteProposal $num>]<'info> {
-                #[account(mut)]
-                pub payer: Signer<'info>,
-                /// CHECK: no need to be checked
-                pub speaker: UncheckedAccount<'info>,
-                pub proposal_registry: Box<Account<'info, ProposalRegistry>>,
-                #[account(
-                    mut,
-                    close = speaker,
-                    has_one = proposal_registry,
-                    has_one = speaker,
-                    constraint = proposal.status == ProposalStatus::Approved @ ZktGuardianError::ProposalNotApproved,
-                    constraint = proposal.target_accounts.len() == $num @ ZktGuardianError::UnmatchedTargetAccountsLength,
-                )]
-                pub proposal: Box<Account<'info, Proposal>>,
-                #[account(
-                    mut,
-                    constraint = compliance_registry.access_registry == proposal_registry.access_registry @ ZktGuardianError::UnmatchedAccessRegistry,
-                )]
-                pub compliance_registry: Box<Account<'info, ComplianceRegistry>>,
-                $(
-                    #[account(
-                        init_if_needed,
-                        payer = payer,
-                        space = 8 + Compliance::SIZE,
-                        seeds = [
-                            b"compliance",
-                            compliance_registry.key().as_ref(),
-                            proposal.target_accounts[$idx].as_ref(),
-                        ],
-                        bump,
-                    )]
-                    pub [<compliance_ $idx>]: Box<Account<'info, Compliance>>,
-                )+
-                // system program
-                pub system_program: Program<'info, System>,
-            }
+#[event]
+pub struct VetoProposalEvent {
+    pub vetoer: Pubkey,
+    pub speaker: Pubkey,
+    pub proposal_registry: Pubkey,
+    pub proposal: Pubkey,
+    pub reason: String,
+    pub timestamp: UnixTimestamp,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VetoProposal<'info> {
+    pub vetoer: Signer<'info>,
+    pub access_registry: Account<'info, AccessRegistry>,
+    #[account(
+        has_one = access_registry,
+        constraint = access.owner == vetoer.key(),
+        constraint = access.role == Role::Vetoer @ ZktGuardianError::MustBeVetoer,
+        constraint = access.is_active(clock.unix_timestamp) @ ZktGuardianError::RoleExpired,
+    )]
+    pub access: Account<'info, Access>,
+    #[account(mut, has_one = access_registry)]
+    pub proposal_registry: Account<'info, ProposalRegistry>,
+    #[account(mut, has_one = proposal_registry)]
+    pub proposal: Box<Account<'info, Proposal>>,
+    // system programs
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub(crate) fn _veto_proposal(ctx: Context<VetoProposal>, reason: String) -> Result<()> {
+    ctx.accounts.proposal.veto(reason.clone(), &mut ctx.accounts.proposal_registry)?;
+
+    emit_cpi!(VetoProposalEvent {
+        vetoer: ctx.accounts.vetoer.key(),
+        speaker: ctx.accounts.proposal.speaker,
+        proposal_registry: ctx.accounts.proposal_registry.key(),
+        proposal: ctx.accounts.proposal.key(),
+        reason,
+        timestamp: ctx.accounts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct CancelProposalEvent {
+    pub speaker: Pubkey,
+    pub authority: Pubkey,
+    pub proposal_registry: Pubkey,
+    pub proposal: Pubkey,
+    pub timestamp: UnixTimestamp,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: no need to be checked
+    #[account(mut)]
+    pub speaker: UncheckedAccount<'info>,
+    pub access_registry: Account<'info, AccessRegistry>,
+    // only required when the authority is not the speaker
+    #[account(
+        has_one = access_registry,
+        constraint = access.owner == authority.key(),
+        constraint = access.role == Role::ProposalManager @ ZktGuardianError::MustBeProposalManager,
+        constraint = access.is_active(clock.unix_timestamp) @ ZktGuardianError::RoleExpired,
+    )]
+    pub access: Option<Account<'info, Access>>,
+    #[account(mut, has_one = access_registry)]
+    pub proposal_registry: Account<'info, ProposalRegistry>,
+    #[account(mut, close = speaker, has_one = proposal_registry, has_one = speaker)]
+    pub proposal: Box<Account<'info, Proposal>>,
+    // system programs
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub(crate) fn _cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.speaker.key() || ctx.accounts.access.is_some(),
+        ZktGuardianError::MustBeSpeakerOrProposalManager,
+    );
+    ctx.accounts.proposal.cancel(&mut ctx.accounts.proposal_registry)?;
+
+    emit_cpi!(CancelProposalEvent {
+        speaker: ctx.accounts.speaker.key(),
+        authority: ctx.accounts.authority.key(),
+        proposal_registry: ctx.accounts.proposal_registry.key(),
+        proposal: ctx.accounts.proposal.key(),
+        timestamp: ctx.accounts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ComplianceStatusChangedEvent {
+    pub compliance: Pubkey,
+    pub target_account: Pubkey,
+    pub previous_proposal: Pubkey,
+    pub proposal: Pubkey,
+    pub previous_status: ComplianceStatus,
+    pub status: ComplianceStatus,
+    pub timestamp: UnixTimestamp,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: no need to be checked
+    #[account(mut)]
+    pub speaker: UncheckedAccount<'info>,
+    pub proposal_registry: Box<Account<'info, ProposalRegistry>>,
+    #[account(
+        mut,
+        has_one = proposal_registry,
+        has_one = speaker,
+        constraint = proposal.status == ProposalStatus::Approved @ ZktGuardianError::ProposalNotApproved,
+        constraint = proposal.is_executable(clock.unix_timestamp) @ ZktGuardianError::ExecutionDelayNotPassed,
+        constraint = !proposal.is_execution_expired(clock.unix_timestamp) @ ZktGuardianError::ExecutionDeadlinePassed,
+        constraint = proposal.proposal_targets.is_none() @ ZktGuardianError::UnmatchedProposalTargets,
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    #[account(
+        mut,
+        address = proposal_registry.access_registry @ ZktGuardianError::UnmatchedAccessRegistry,
+    )]
+    pub access_registry: Box<Account<'info, AccessRegistry>>,
+    #[account(
+        mut,
+        constraint = compliance_registry.access_registry == proposal_registry.access_registry @ ZktGuardianError::UnmatchedAccessRegistry,
+    )]
+    pub compliance_registry: Box<Account<'info, ComplianceRegistry>>,
+    // system program
+    pub clock: Sysvar<'info, Clock>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts: compliance or access PDAs of the next unexecuted target accounts
+}
+
+/// Executes the proposal for the targets following `proposal.executed`, so a proposal
+/// too large for one transaction can be executed over several. The proposal is closed
+/// once every target account has been executed.
+pub(crate) fn _execute_proposal<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+) -> Result<()> {
+    let start = ctx.accounts.proposal.executed as usize;
+    let end = start + ctx.remaining_accounts.len() / ctx.accounts.proposal.kind.accounts_per_target();
+    require_gte!(
+        ctx.accounts.proposal.target_accounts.len(),
+        end,
+        ZktGuardianError::UnmatchedTargetAccountsLength,
+    );
+
+    let events = execute_targets(
+        ctx.program_id,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        &mut ctx.accounts.access_registry,
+        &mut ctx.accounts.compliance_registry,
+        &ctx.accounts.proposal,
+        &ctx.accounts.proposal.target_accounts[start..end],
+        &ctx.accounts.clock,
+        ctx.remaining_accounts,
+    )?;
+    for event in events {
+        emit_cpi!(event);
+    }
+    ctx.accounts.proposal.executed = end as u32;
+
+    if ctx.accounts.proposal.is_executed() {
+        ctx.accounts.proposal.close(ctx.accounts.speaker.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteProposalBatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: no need to be checked
+    #[account(mut)]
+    pub speaker: UncheckedAccount<'info>,
+    pub proposal_registry: Box<Account<'info, ProposalRegistry>>,
+    #[account(
+        mut,
+        has_one = proposal_registry,
+        has_one = speaker,
+        constraint = proposal.status == ProposalStatus::Approved @ ZktGuardianError::ProposalNotApproved,
+        constraint = proposal.is_executable(clock.unix_timestamp) @ ZktGuardianError::ExecutionDelayNotPassed,
+        constraint = !proposal.is_execution_expired(clock.unix_timestamp) @ ZktGuardianError::ExecutionDeadlinePassed,
+        constraint = proposal.proposal_targets == Some(proposal_targets.key()) @ ZktGuardianError::UnmatchedProposalTargets,
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    #[account(mut, has_one = speaker, has_one = proposal)]
+    pub proposal_targets: Box<Account<'info, ProposalTargets>>,
+    #[account(
+        mut,
+        address = proposal_registry.access_registry @ ZktGuardianError::UnmatchedAccessRegistry,
+    )]
+    pub access_registry: Box<Account<'info, AccessRegistry>>,
+    #[account(
+        mut,
+        constraint = compliance_registry.access_registry == proposal_registry.access_registry @ ZktGuardianError::UnmatchedAccessRegistry,
+    )]
+    pub compliance_registry: Box<Account<'info, ComplianceRegistry>>,
+    // system program
+    pub clock: Sysvar<'info, Clock>,
+    pub system_program: Program<'info, System>,
+    // remaining accounts: compliance or access PDAs of target accounts `start..start + count`
+}
+
+/// Executes the targets `start..start + count` of a proposal with a separate target list.
+/// `start` must match the execution cursor so concurrent batches cannot overlap. The
+/// proposal and its target list are closed once every target account has been executed.
+pub(crate) fn _execute_proposal_batch<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteProposalBatch<'info>>,
+    start: u32,
+    count: u32,
+) -> Result<()> {
+    require_eq!(start, ctx.accounts.proposal.executed, ZktGuardianError::UnmatchedExecutionCursor);
+    let end = start + count;
+    require_gte!(
+        ctx.accounts.proposal.target_count,
+        end,
+        ZktGuardianError::UnmatchedTargetAccountsLength,
+    );
+
+    let events = execute_targets(
+        ctx.program_id,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        &mut ctx.accounts.access_registry,
+        &mut ctx.accounts.compliance_registry,
+        &ctx.accounts.proposal,
+        &ctx.accounts.proposal_targets.target_accounts[start as usize..end as usize],
+        &ctx.accounts.clock,
+        ctx.remaining_accounts,
+    )?;
+    for event in events {
+        emit_cpi!(event);
+    }
+    ctx.accounts.proposal.executed = end;
+
+    if ctx.accounts.proposal.is_executed() {
+        ctx.accounts.proposal.close(ctx.accounts.speaker.to_account_info())?;
+        ctx.accounts.proposal_targets.close(ctx.accounts.speaker.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_targets<'info>(
+    program_id: &Pubkey,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    access_registry: &mut Account<'info, AccessRegistry>,
+    compliance_registry: &mut Account<'info, ComplianceRegistry>,
+    proposal: &Account<'info, Proposal>,
+    target_accounts: &[Pubkey],
+    clock: &Clock,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<Vec<ComplianceStatusChangedEvent>> {
+    let accounts_per_target = proposal.kind.accounts_per_target();
+    let accounts = remaining_accounts.chunks_exact(accounts_per_target);
+    require!(
+        accounts.remainder().is_empty() && accounts.len() == target_accounts.len(),
+        ZktGuardianError::UnmatchedTargetAccountsLength,
+    );
+
+    let access_registry_key = access_registry.key();
+    let compliance_registry_key = compliance_registry.key();
+    let mut events = Vec::new();
+    for (target_account, accounts) in target_accounts.iter().zip(accounts) {
+        match proposal.kind {
+            ProposalKind::Register => {
+                let compliance = &accounts[0];
+                let seeds = [b"compliance".as_ref(), compliance_registry_key.as_ref(), target_account.as_ref()];
+                let bump = find_bump(program_id, &seeds, compliance, ZktGuardianError::UnmatchedComplianceAccount)?;
+                // an existing entry moves its counters over to the new status instead of being
+                // registered a second time
+                if compliance.owner == program_id {
+                    // entries with the original layout only decode once grown
+                    realloc_if_needed(payer, system_program, 8 + Compliance::SIZE, compliance)?;
+                    let mut data = Compliance::try_deserialize(&mut &compliance.try_borrow_data()?[..])?;
+                    let previous_status = data.status;
+                    compliance_registry.revoke(data.status, data.categories);
+                    data.update(proposal.key(), proposal);
+                    data.try_serialize(&mut &mut compliance.try_borrow_mut_data()?[..])?;
+                    compliance_registry.register(data.status, data.categories);
+
+                    events.push(ComplianceStatusChangedEvent {
+                        compliance: compliance.key(),
+                        target_account: *target_account,
+                        previous_proposal: data.previous_proposal.unwrap_or_default(),
+                        proposal: data.proposal,
+                        previous_status,
+                        status: data.status,
+                        timestamp: clock.unix_timestamp,
+                    });
+                    continue;
+                }
+                init_pda_if_needed(
+                    program_id,
+                    payer,
+                    system_program,
+                    &[&seeds[..], &[&[bump]]].concat(),
+                    8 + Compliance::SIZE,
+                    compliance,
+                )?;
+
+                Compliance::new(bump, payer.key(), proposal.key(), proposal)
+                    .try_serialize(&mut &mut compliance.try_borrow_mut_data()?[..])?;
+                compliance_registry.register(proposal.compliance_status, proposal.categories);
+            },
+            ProposalKind::Revoke => {
+                let compliance = &accounts[0];
+                let seeds = [b"compliance".as_ref(), compliance_registry_key.as_ref(), target_account.as_ref()];
+                find_bump(program_id, &seeds, compliance, ZktGuardianError::UnmatchedComplianceAccount)?;
+                // the entry may have been revoked since the proposal was created
+                if compliance.owner != program_id {
+                    continue;
+                }
+                let compliance_payer = &accounts[1];
+                // entries with the original layout only decode once grown, the top-up goes to
+                // the entry payer with the rest of the rent
+                realloc_if_needed(payer, system_program, 8 + Compliance::SIZE, compliance)?;
+                let data = Compliance::try_deserialize(&mut &compliance.try_borrow_data()?[..])?;
+                require_keys_eq!(compliance_payer.key(), data.payer, ZktGuardianError::UnmatchedCompliancePayer);
+
+                close_account(compliance, compliance_payer)?;
+                compliance_registry.revoke(data.status, data.categories);
+            },
+            ProposalKind::Renew => {
+                let compliance = &accounts[0];
+                let seeds = [b"compliance".as_ref(), compliance_registry_key.as_ref(), target_account.as_ref()];
+                find_bump(program_id, &seeds, compliance, ZktGuardianError::UnmatchedComplianceAccount)?;
+                // the entry may have been revoked since the proposal was created
+                if compliance.owner != program_id {
+                    continue;
+                }
+
+                // entries with the original layout only decode once grown
+                realloc_if_needed(payer, system_program, 8 + Compliance::SIZE, compliance)?;
+                let mut data = Compliance::try_deserialize(&mut &compliance.try_borrow_data()?[..])?;
+                data.valid_until = proposal.valid_until;
+                data.try_serialize(&mut &mut compliance.try_borrow_mut_data()?[..])?;
+            },
+            ProposalKind::AssignRole { role } => {
+                let access = &accounts[0];
+                let role_seed = role.to_string();
+                let seeds = [b"access".as_ref(), access_registry_key.as_ref(), role_seed.as_bytes(), target_account.as_ref()];
+                let bump = find_bump(program_id, &seeds, access, ZktGuardianError::UnmatchedAccessAccount)?;
+                // the role may have been assigned since the proposal was created
+                if access.owner == program_id {
+                    continue;
+                }
+                init_pda_if_needed(
+                    program_id,
+                    payer,
+                    system_program,
+                    &[&seeds[..], &[&[bump]]].concat(),
+                    8 + Access::SIZE,
+                    access,
+                )?;
+
+                // governed guardians start with weight 1, see `SetGuardianWeight`
+                access_registry.assign_role(role, 1);
+                Access {
+                    access_registry: access_registry_key,
+                    role,
+                    owner: *target_account,
+                    expires_at: None,
+                    epoch: access_registry.guardian_epoch,
+                    weight: 1,
+                    delegate: None,
+                    payer: payer.key(),
+                }.try_serialize(&mut &mut access.try_borrow_mut_data()?[..])?;
+            },
+            ProposalKind::SetGuardianWeight { weight } => {
+                let access = &accounts[0];
+                let role_seed = Role::Guardian.to_string();
+                let seeds = [b"access".as_ref(), access_registry_key.as_ref(), role_seed.as_bytes(), target_account.as_ref()];
+                find_bump(program_id, &seeds, access, ZktGuardianError::UnmatchedAccessAccount)?;
+                // the guardian may have been revoked since the proposal was created
+                if access.owner != program_id {
+                    continue;
+                }
+
+                let mut data = Access::try_deserialize(&mut &access.try_borrow_data()?[..])?;
+                access_registry.update_guardian_weight(&mut data, weight)?;
+                data.try_serialize(&mut &mut access.try_borrow_mut_data()?[..])?;
+            },
+            ProposalKind::RevokeRole { role } => {
+                let access = &accounts[0];
+                let role_seed = role.to_string();
+                let seeds = [b"access".as_ref(), access_registry_key.as_ref(), role_seed.as_bytes(), target_account.as_ref()];
+                find_bump(program_id, &seeds, access, ZktGuardianError::UnmatchedAccessAccount)?;
+                // the role may have been revoked since the proposal was created
+                if access.owner != program_id {
+                    continue;
+                }
+
+                let access_payer = &accounts[1];
+                let data = Access::try_deserialize(&mut &access.try_borrow_data()?[..])?;
+                require_keys_eq!(access_payer.key(), data.payer, ZktGuardianError::UnmatchedAccessPayer);
+                close_account(access, access_payer)?;
+                access_registry.revoke_role(role, data.weight);
+            },
+        }
+    }
+
+    Ok(events)
+}
+
+/// Validates `account` against the PDA of `seeds`, returning the canonical bump.
+fn find_bump(
+    program_id: &Pubkey,
+    seeds: &[&[u8]],
+    account: &AccountInfo,
+    error: ZktGuardianError,
+) -> Result<u8> {
+    let (address, bump) = Pubkey::find_program_address(seeds, program_id);
+    require_keys_eq!(account.key(), address, error);
+
+    Ok(bump)
+}
+
+fn init_pda_if_needed<'info>(
+    program_id: &Pubkey,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    seeds: &[&[u8]],
+    space: usize,
+    account: &AccountInfo<'info>,
+) -> Result<()> {
+    if account.owner == program_id {
+        return Ok(());
+    }
+    let signer_seeds = &[seeds];
+    let lamports = Rent::get()?.minimum_balance(space);
+
+    if account.lamports() == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+                signer_seeds,
+            ),
+            lamports,
+            space as u64,
+            program_id,
+        )?;
+    } else {
+        // the address was pre-funded, so it can only be topped up and claimed
+        let top_up = lamports.saturating_sub(account.lamports());
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: payer.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Allocate { account_to_allocate: account.clone() },
+                signer_seeds,
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Assign { account_to_assign: account.clone() },
+                signer_seeds,
+            ),
+            program_id,
+        )?;
+    }
+
+    Ok(())
+}
 
-            pub(crate) fn [<_execute_proposal_ $num>](
-                ctx: Context<[<ExecuteProposal $num>]>,
-            ) -> Result<()> {
-                ctx.accounts.compliance_registry.register(ctx.accounts.proposal.is_whitelist);
-                
-                $(
-                    let compliance = Compliance::new(
-                        ctx.bumps.[<compliance_ $idx>],
-                        ctx.accounts.payer.key(),
-                        ctx.accounts.proposal.key(),
-                        &ctx.accounts.proposal,
-                    );
-                    ctx.accounts.[<compliance_ $idx>].set_inner(compliance);
-                )+
-
-                Ok(())
+/// Grows an account written with an older, smaller layout.
+pub(crate) fn realloc_if_needed<'info>(
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    account: &AccountInfo<'info>,
+) -> Result<()> {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+    let top_up = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    account.realloc(space, true).map_err(Into::into)
+}
+
+fn close_account<'info>(account: &AccountInfo<'info>, destination: &AccountInfo<'info>) -> Result<()> {
+    let lamports = account.lamports();
+    **destination.lamports.borrow_mut() += lamports;
+    **account.lamports.borrow_mut() = 0;
+
+    account.assign(&System::id());
+    account.realloc(0, false).map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: UnixTimestamp = 1_700_000_000;
+
+    struct Delegation {
+        access_registry: Pubkey,
+        proposal: Pubkey,
+        guardian: Pubkey,
+    }
+
+    /// Owned (key, owner, data) of an account passed in remaining accounts.
+    type TestAccount = (Pubkey, Pubkey, Vec<u8>);
+
+    impl Delegation {
+        fn new() -> Self {
+            Delegation {
+                access_registry: Pubkey::new_unique(),
+                proposal: Pubkey::new_unique(),
+                guardian: Pubkey::new_unique(),
             }
         }
-    };
-}
-
-impl_execute_proposal!(1, [0]);
-impl_execute_proposal!(2, [0, 1]);
-impl_execute_proposal!(3, [0, 1, 2]);
-impl_execute_proposal!(4, [0, 1, 2, 3]);
-impl_execute_proposal!(5, [0, 1, 2, 3, 4]);
-impl_execute_proposal!(6, [0, 1, 2, 3, 4, 5]);
-impl_execute_proposal!(7, [0, 1, 2, 3, 4, 5, 6]);
-impl_execute_proposal!(8, [0, 1, 2, 3, 4, 5, 6, 7]);
-impl_execute_proposal!(9, [0, 1, 2, 3, 4, 5, 6, 7, 8]);
-impl_execute_proposal!(10, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
-impl_execute_proposal!(11, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
-impl_execute_proposal!(12, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
-impl_execute_proposal!(13, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
-impl_execute_proposal!(14, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]);
-impl_execute_proposal!(15, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
-impl_execute_proposal!(16, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
-impl_execute_proposal!(17, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]);
-impl_execute_proposal!(18, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17]);
-impl_execute_proposal!(19, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18]);
-impl_execute_proposal!(20, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19]);
\ No newline at end of file
+
+        fn delegator(&self) -> [TestAccount; 2] {
+            let owner = Pubkey::new_unique();
+            let access = Access {
+                access_registry: self.access_registry,
+                role: Role::Guardian,
+                owner,
+                expires_at: None,
+                epoch: 0,
+                weight: 1,
+                delegate: Some(self.guardian),
+                payer: owner,
+            };
+            let mut data = vec![];
+            access.try_serialize(&mut data).unwrap();
+            let (vote_record, _) = Pubkey::find_program_address(
+                &[b"vote", self.proposal.as_ref(), owner.as_ref()],
+                &crate::ID,
+            );
+
+            [
+                (Pubkey::new_unique(), crate::ID, data),
+                (vote_record, System::id(), vec![]),
+            ]
+        }
+
+        fn check(&self, accounts: &mut [TestAccount]) -> Result<Vec<(Access, u8)>> {
+            let mut lamports = vec![0; accounts.len()];
+            let infos: Vec<AccountInfo> = accounts
+                .iter_mut()
+                .zip(lamports.iter_mut())
+                .map(|((key, owner, data), lamports)| {
+                    AccountInfo::new(key, false, true, lamports, data, owner, false, 0)
+                })
+                .collect();
+
+            check_delegators(
+                &crate::ID,
+                &self.access_registry,
+                &self.proposal,
+                0,
+                &self.guardian,
+                NOW,
+                &infos,
+            )
+        }
+    }
+
+    #[test]
+    fn delegators_are_counted_once() {
+        let delegation = Delegation::new();
+        let mut accounts = [delegation.delegator(), delegation.delegator()].concat();
+        let delegators = delegation.check(&mut accounts).unwrap();
+        assert_eq!(delegators.len(), 2);
+
+        let duplicate = delegation.delegator();
+        let mut accounts = [duplicate.clone(), duplicate].concat();
+        let err = delegation.check(&mut accounts).err().unwrap();
+        assert_eq!(err, ZktGuardianError::AlreadyVoted.into());
+    }
+
+    #[test]
+    fn delegator_that_already_voted_is_rejected() {
+        let delegation = Delegation::new();
+        let mut accounts = delegation.delegator();
+        // an existing vote record is owned by the program
+        accounts[1].1 = crate::ID;
+        let err = delegation.check(&mut accounts).err().unwrap();
+        assert_eq!(err, ZktGuardianError::AlreadyVoted.into());
+    }
+
+    #[test]
+    fn delegator_must_delegate_to_guardian() {
+        let delegation = Delegation::new();
+        let mut accounts = delegation.delegator();
+        let other = Delegation { guardian: Pubkey::new_unique(), ..delegation };
+        let err = other.check(&mut accounts).err().unwrap();
+        assert_eq!(err, ZktGuardianError::UnmatchedDelegate.into());
+
+        let mut accounts = other.delegator();
+        let err = other.check(&mut accounts[..1]).err().unwrap();
+        assert_eq!(err, ZktGuardianError::UnmatchedDelegatorAccounts.into());
+    }
+}
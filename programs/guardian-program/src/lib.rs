@@ -18,67 +18,187 @@ pub mod guardian_program {
         _init_access_registry(ctx)
     }
 
-    pub fn assign_role(ctx: Context<AssignRole>, role: Role) -> Result<()> {
-        _assign_role(ctx, role)
+    pub fn migrate_access_registry(ctx: Context<MigrateAccessRegistry>) -> Result<()> {
+        _migrate_access_registry(ctx)
+    }
+
+    pub fn migrate_access(ctx: Context<MigrateAccess>) -> Result<()> {
+        _migrate_access(ctx)
+    }
+
+    pub fn assign_role(
+        ctx: Context<AssignRole>,
+        role: Role,
+        expires_at: Option<i64>,
+        weight: u32,
+    ) -> Result<()> {
+        _assign_role(ctx, role, expires_at, weight)
     }
 
     pub fn revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
         _revoke_role(ctx)
     }
 
+    pub fn update_guardian_weight(ctx: Context<UpdateGuardianWeight>, weight: u32) -> Result<()> {
+        _update_guardian_weight(ctx, weight)
+    }
+
+    pub fn delegate_vote(ctx: Context<DelegateVote>) -> Result<()> {
+        _delegate_vote(ctx)
+    }
+
+    pub fn undelegate_vote(ctx: Context<UndelegateVote>) -> Result<()> {
+        _undelegate_vote(ctx)
+    }
+
+    pub fn prune_expired_role(ctx: Context<PruneExpiredRole>) -> Result<()> {
+        _prune_expired_role(ctx)
+    }
+
+    pub fn set_vote_threshold(ctx: Context<SetVoteThreshold>, quorum: u32, threshold: Threshold) -> Result<()> {
+        _set_vote_threshold(ctx, quorum, threshold)
+    }
+
+    pub fn set_min_community_support(
+        ctx: Context<SetMinCommunitySupport>,
+        min_community_support: u32,
+    ) -> Result<()> {
+        _set_min_community_support(ctx, min_community_support)
+    }
+
+    pub fn enable_role_governance(ctx: Context<EnableRoleGovernance>) -> Result<()> {
+        _enable_role_governance(ctx)
+    }
+
+    pub fn set_admin_signers(
+        ctx: Context<SetAdminSigners>,
+        admin_signers: Vec<Pubkey>,
+        admin_threshold: u8,
+    ) -> Result<()> {
+        _set_admin_signers(ctx, admin_signers, admin_threshold)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>) -> Result<()> {
+        _propose_admin(ctx)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        _accept_admin(ctx)
+    }
+
     pub fn init_proposal_registry(ctx: Context<InitProposalRegistry>) -> Result<()> {
         _init_proposal_registry(ctx)
     }
 
-    pub fn create_proposal(ctx: Context<CreateProposal>, description: String, is_whitelist: bool) -> Result<()> {
-        _create_proposal(ctx, description, is_whitelist)
+    pub fn migrate_proposal_registry(ctx: Context<MigrateProposalRegistry>) -> Result<()> {
+        _migrate_proposal_registry(ctx)
     }
 
-    pub fn vote_for_proposal(ctx: Context<VoteForProposal>) -> Result<()> {
-        _vote_for_proposal(ctx)
+    pub fn set_voting_period(ctx: Context<SetVotingPeriod>, voting_period: i64) -> Result<()> {
+        _set_voting_period(ctx, voting_period)
+    }
+
+    pub fn set_execution_delay(
+        ctx: Context<SetExecutionDelay>,
+        execution_delay: i64,
+        blacklist_exempt: bool,
+    ) -> Result<()> {
+        _set_execution_delay(ctx, execution_delay, blacklist_exempt)
+    }
+
+    pub fn set_execution_period(ctx: Context<SetExecutionPeriod>, execution_period: i64) -> Result<()> {
+        _set_execution_period(ctx, execution_period)
+    }
+
+    pub fn init_proposal_targets(ctx: Context<InitProposalTargets>, target_accounts: Vec<Pubkey>) -> Result<()> {
+        _init_proposal_targets(ctx, target_accounts)
+    }
+
+    pub fn append_proposal_targets(ctx: Context<AppendProposalTargets>, target_accounts: Vec<Pubkey>) -> Result<()> {
+        _append_proposal_targets(ctx, target_accounts)
+    }
+
+    pub fn close_proposal_targets(ctx: Context<CloseProposalTargets>) -> Result<()> {
+        _close_proposal_targets(ctx)
+    }
+
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        description: String,
+        compliance_status: ComplianceStatus,
+        kind: ProposalKind,
+        valid_until: Option<i64>,
+        risk_score: u8,
+        categories: u8,
+    ) -> Result<()> {
+        _create_proposal(ctx, description, compliance_status, kind, valid_until, risk_score, categories)
+    }
+
+    pub fn vote_for_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, VoteForProposal<'info>>,
+        choice: VoteChoice,
+    ) -> Result<()> {
+        _vote_for_proposal(ctx, choice)
+    }
+
+    pub fn signal_proposal(ctx: Context<SignalProposal>, support: bool) -> Result<()> {
+        _signal_proposal(ctx, support)
+    }
+
+    pub fn close_signal_record(ctx: Context<CloseSignalRecord>) -> Result<()> {
+        _close_signal_record(ctx)
+    }
+
+    pub fn close_vote_record(ctx: Context<CloseVoteRecord>) -> Result<()> {
+        _close_vote_record(ctx)
     }
 
     pub fn reject_expired_proposal(ctx: Context<RejectExpiredProposal>) -> Result<()> {
         _reject_expired_proposal(ctx)
     }
 
-    macro_rules! impl_execute_proposal {
-        ($num:tt) => {
-            paste::paste! {
-                pub fn [<execute_proposal_ $num>](
-                    ctx: Context<[<ExecuteProposal $num>]>,
-                ) -> Result<()> {
-                    [<_execute_proposal_ $num>](ctx)
-                }
-            }
-        }
-    }
-
-    impl_execute_proposal!(1);
-    impl_execute_proposal!(2);
-    impl_execute_proposal!(3);
-    impl_execute_proposal!(4);
-    impl_execute_proposal!(5);
-    impl_execute_proposal!(6);
-    impl_execute_proposal!(7);
-    impl_execute_proposal!(8);
-    impl_execute_proposal!(9);
-    impl_execute_proposal!(10);
-    impl_execute_proposal!(11);
-    impl_execute_proposal!(12);
-    impl_execute_proposal!(13);
-    impl_execute_proposal!(14);
-    impl_execute_proposal!(15);
-    impl_execute_proposal!(16);
-    impl_execute_proposal!(17);
-    impl_execute_proposal!(18);
-    impl_execute_proposal!(19);
-    impl_execute_proposal!(20);
-    
+    pub fn close_rejected_proposal(ctx: Context<CloseRejectedProposal>) -> Result<()> {
+        _close_rejected_proposal(ctx)
+    }
+
+    pub fn expire_proposal(ctx: Context<ExpireProposal>) -> Result<()> {
+        _expire_proposal(ctx)
+    }
+
+    pub fn veto_proposal(ctx: Context<VetoProposal>, reason: String) -> Result<()> {
+        _veto_proposal(ctx, reason)
+    }
+
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        _cancel_proposal(ctx)
+    }
+
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+    ) -> Result<()> {
+        _execute_proposal(ctx)
+    }
+
+    pub fn execute_proposal_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteProposalBatch<'info>>,
+        start: u32,
+        count: u32,
+    ) -> Result<()> {
+        _execute_proposal_batch(ctx, start, count)
+    }
+
     pub fn init_compliance_registry(ctx: Context<InitComplianceRegistry>) -> Result<()> {
         _init_compliance_registry(ctx)
     }
     
+    pub fn migrate_compliance_registry(ctx: Context<MigrateComplianceRegistry>) -> Result<()> {
+        _migrate_compliance_registry(ctx)
+    }
+
+    pub fn migrate_compliance(ctx: Context<MigrateCompliance>) -> Result<()> {
+        _migrate_compliance(ctx)
+    }
+
     pub fn revoke_compliance(ctx: Context<RevokeCompliance>) -> Result<()> {
         _revoke_compliance(ctx)
     }
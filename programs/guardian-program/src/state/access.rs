@@ -1,5 +1,9 @@
 use std::fmt::Display;
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::clock::UnixTimestamp};
+
+use crate::error::ZktGuardianError;
+
+pub const MAX_ADMIN_SIGNERS: usize = 10;
 
 #[derive(Clone, Copy, PartialEq, AnchorSerialize, AnchorDeserialize)]
 pub enum Role {
@@ -7,6 +11,7 @@ pub enum Role {
     Voter,
     Guardian,
     ProposalManager,
+    Vetoer,
 }
 
 impl Display for Role {
@@ -16,11 +21,42 @@ impl Display for Role {
             Self::Voter => "voter".to_string(),
             Self::Guardian => "guardian".to_string(),
             Self::ProposalManager => "proposal_manager".to_string(),
+            Self::Vetoer => "vetoer".to_string(),
         };
         write!(f, "{}", str)
     }
 }
 
+#[derive(Clone, Copy, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub enum Threshold {
+    /// More than half of the guardian weight.
+    Majority,
+    /// At least `numerator / denominator` of the guardian weight.
+    Ratio { numerator: u16, denominator: u16 },
+    /// At least `count` guardian weight, regardless of the total guardian weight.
+    Fixed { count: u32 },
+}
+
+impl Threshold {
+    pub fn is_reached(&self, weight: u64, guardian_weight: u64) -> bool {
+        match *self {
+            Self::Majority => weight * 2 > guardian_weight,
+            Self::Ratio { numerator, denominator } => {
+                weight * denominator as u64 >= guardian_weight * numerator as u64
+            },
+            Self::Fixed { count } => weight >= count as u64,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        match *self {
+            Self::Majority => true,
+            Self::Ratio { numerator, denominator } => numerator > 0 && numerator <= denominator,
+            Self::Fixed { count } => count > 0,
+        }
+    }
+}
+
 #[account]
 pub struct AccessRegistry {
     pub admin: Pubkey,
@@ -29,29 +65,105 @@ pub struct AccessRegistry {
     pub voters: u32,
     pub guardians: u32,
     pub proposal_managers: u32,
-    pub _reserve: [u8; 32],
+    // zeroed reserve bytes decode as no quorum, a simple majority and no role governance
+    pub quorum: u32,
+    pub threshold: Threshold,
+    // role changes go through proposals only once enabled
+    pub role_governance: bool,
+    // bumped on every guardian assignment or revocation
+    pub guardian_epoch: u64,
+    // community support required before guardians can approve, zero disables it
+    pub min_community_support: u32,
+    pub vetoers: u32,
+    pub _reserve: [u8; 6],
+    // appended after the original layout, older registries need `migrate_access_registry`
+    pub pending_admin: Option<Pubkey>,
+    // replaces `admin` with an M-of-N signer set once configured
+    pub admin_threshold: u8,
+    pub admin_signers: Vec<Pubkey>,
+    // backfilled on migration, guardians assigned before weights existed weigh 1
+    pub guardian_weight: u64,
 }
 
 impl AccessRegistry {
-    pub const SIZE: usize = std::mem::size_of::<Self>();
+    pub const SIZE: usize = std::mem::size_of::<Self>() + MAX_ADMIN_SIGNERS * 32;
+
+    pub(crate) fn check_admin_signers(&self, signers: &[Pubkey]) -> Result<()> {
+        if self.admin_signers.is_empty() {
+            require!(signers.contains(&self.admin), ZktGuardianError::MustBeAdmin);
+        } else {
+            let approvals = self.admin_signers
+                .iter()
+                .filter(|admin_signer| signers.contains(admin_signer))
+                .count();
+            require_gte!(approvals, self.admin_threshold as usize, ZktGuardianError::NotEnoughAdminSigners);
+        }
 
-    pub(crate) fn assign_role(&mut self, role: Role) {
+        Ok(())
+    }
+
+    /// An empty signer set with a zero threshold hands authority back to `admin`.
+    pub(crate) fn set_admin_signers(&mut self, admin_signers: Vec<Pubkey>, admin_threshold: u8) -> Result<()> {
+        require_gte!(MAX_ADMIN_SIGNERS, admin_signers.len(), ZktGuardianError::InvalidAdminSigners);
+        require_gte!(admin_signers.len(), admin_threshold as usize, ZktGuardianError::InvalidAdminSigners);
+        require!(
+            admin_signers.is_empty() == (admin_threshold == 0),
+            ZktGuardianError::InvalidAdminSigners,
+        );
+        for (i, admin_signer) in admin_signers.iter().enumerate() {
+            require!(!admin_signers[..i].contains(admin_signer), ZktGuardianError::InvalidAdminSigners);
+        }
+        self.admin_signers = admin_signers;
+        self.admin_threshold = admin_threshold;
+
+        Ok(())
+    }
+
+    pub(crate) fn assign_role(&mut self, role: Role, weight: u32) {
         match role {
             Role::Speaker => { self.speakers += 1; },
             Role::Voter => { self.voters += 1; },
-            Role::Guardian => { self.guardians += 1; },
+            Role::Guardian => {
+                self.guardians += 1;
+                self.guardian_weight += weight as u64;
+                self.guardian_epoch += 1;
+            },
             Role::ProposalManager => { self.proposal_managers += 1; },
+            Role::Vetoer => { self.vetoers += 1; },
         }
     }
 
-    pub(crate) fn revoke_role(&mut self, role: Role) {
+    pub(crate) fn set_threshold(&mut self, quorum: u32, threshold: Threshold) -> Result<()> {
+        require!(threshold.is_valid(), ZktGuardianError::InvalidThreshold);
+        self.quorum = quorum;
+        self.threshold = threshold;
+
+        Ok(())
+    }
+
+    pub(crate) fn revoke_role(&mut self, role: Role, weight: u32) {
         match role {
             Role::Speaker => { self.speakers -= 1; },
             Role::Voter => { self.voters -= 1; },
-            Role::Guardian => { self.guardians -= 1; },
+            Role::Guardian => {
+                self.guardians -= 1;
+                self.guardian_weight -= weight as u64;
+                self.guardian_epoch += 1;
+            },
             Role::ProposalManager => { self.proposal_managers -= 1; },
+            Role::Vetoer => { self.vetoers -= 1; },
         }
     }
+
+    pub(crate) fn update_guardian_weight(&mut self, access: &mut Access, weight: u32) -> Result<()> {
+        require_gt!(weight, 0, ZktGuardianError::InvalidGuardianWeight);
+        self.guardian_weight = self.guardian_weight - access.weight as u64 + weight as u64;
+        self.guardian_epoch += 1;
+        access.weight = weight;
+        access.epoch = self.guardian_epoch;
+
+        Ok(())
+    }
 }
 
 #[account]
@@ -59,8 +171,175 @@ pub struct Access {
     pub access_registry: Pubkey,
     pub role: Role,
     pub owner: Pubkey,
+    pub expires_at: Option<UnixTimestamp>,
+    /// Guardian epoch of the registry right after this role was assigned.
+    pub epoch: u64,
+    /// Voting weight, only used for guardians.
+    pub weight: u32,
+    /// Guardian casting votes on behalf of this guardian.
+    pub delegate: Option<Pubkey>,
+    /// Paid the rent of this account, refunded when a governed revocation closes it.
+    pub payer: Pubkey,
 }
 
 impl Access {
     pub const SIZE: usize = std::mem::size_of::<Self>();
-}
\ No newline at end of file
+
+    pub fn is_active(&self, now: UnixTimestamp) -> bool {
+        match self.expires_at {
+            Some(expires_at) => now < expires_at,
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    #[test]
+    fn ratio_threshold_requires_supermajority() {
+        let threshold = Threshold::Ratio { numerator: 2, denominator: 3 };
+        assert!(!threshold.is_reached(5, 9));
+        assert!(threshold.is_reached(6, 9));
+        assert!(!threshold.is_reached(1, 3));
+        assert!(threshold.is_reached(2, 3));
+    }
+
+    #[test]
+    fn fixed_threshold_ignores_guardian_weight() {
+        let threshold = Threshold::Fixed { count: 3 };
+        assert!(!threshold.is_reached(2, 3));
+        assert!(threshold.is_reached(3, 5));
+        assert!(threshold.is_reached(3, 100));
+    }
+
+    #[test]
+    fn threshold_validity() {
+        assert!(Threshold::Majority.is_valid());
+        assert!(Threshold::Ratio { numerator: 3, denominator: 3 }.is_valid());
+        assert!(!Threshold::Ratio { numerator: 0, denominator: 3 }.is_valid());
+        assert!(!Threshold::Ratio { numerator: 4, denominator: 3 }.is_valid());
+        assert!(Threshold::Fixed { count: 1 }.is_valid());
+        assert!(!Threshold::Fixed { count: 0 }.is_valid());
+    }
+
+    fn registry(admin: Pubkey) -> AccessRegistry {
+        AccessRegistry {
+            admin,
+            bump: [255],
+            speakers: 0,
+            voters: 0,
+            guardians: 0,
+            proposal_managers: 0,
+            quorum: 0,
+            threshold: Threshold::Majority,
+            role_governance: false,
+            guardian_epoch: 0,
+            min_community_support: 0,
+            vetoers: 0,
+            _reserve: [0; 6],
+            pending_admin: None,
+            admin_threshold: 0,
+            admin_signers: vec![],
+            guardian_weight: 0,
+        }
+    }
+
+    #[test]
+    fn admin_signs_alone_without_signer_set() {
+        let admin = Pubkey::new_unique();
+        let registry = registry(admin);
+
+        registry.check_admin_signers(&[admin]).unwrap();
+        let err = registry.check_admin_signers(&[Pubkey::new_unique()]).unwrap_err();
+        assert_eq!(err, ZktGuardianError::MustBeAdmin.into());
+    }
+
+    #[test]
+    fn signer_set_counts_threshold() {
+        let admin = Pubkey::new_unique();
+        let signers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let mut registry = registry(admin);
+        registry.set_admin_signers(signers.clone(), 2).unwrap();
+
+        let err = registry.check_admin_signers(&[admin, signers[0]]).unwrap_err();
+        assert_eq!(err, ZktGuardianError::NotEnoughAdminSigners.into());
+        // a signer listed twice still counts once
+        let err = registry.check_admin_signers(&[signers[0], signers[0]]).unwrap_err();
+        assert_eq!(err, ZktGuardianError::NotEnoughAdminSigners.into());
+        registry.check_admin_signers(&[signers[0], signers[2]]).unwrap();
+
+        // an empty set with a zero threshold hands authority back to the admin
+        registry.set_admin_signers(vec![], 0).unwrap();
+        registry.check_admin_signers(&[admin]).unwrap();
+    }
+
+    #[test]
+    fn invalid_signer_sets() {
+        let mut registry = registry(Pubkey::new_unique());
+        let signer = Pubkey::new_unique();
+        let too_many: Vec<Pubkey> = (0..=MAX_ADMIN_SIGNERS).map(|_| Pubkey::new_unique()).collect();
+
+        for (admin_signers, admin_threshold) in [
+            (vec![signer, signer], 1),
+            (vec![signer], 2),
+            (vec![signer], 0),
+            (vec![], 1),
+            (too_many.clone(), 1),
+        ] {
+            let err = registry.set_admin_signers(admin_signers, admin_threshold).unwrap_err();
+            assert_eq!(err, ZktGuardianError::InvalidAdminSigners.into());
+        }
+        assert!(registry.admin_signers.is_empty());
+
+        registry.set_admin_signers(too_many[..MAX_ADMIN_SIGNERS].to_vec(), 10).unwrap();
+        assert_eq!(registry.admin_signers.len(), MAX_ADMIN_SIGNERS);
+    }
+
+    #[test]
+    fn legacy_registry_decodes_after_realloc() {
+        let admin = Pubkey::new_unique();
+        // discriminator, admin, bump, role counters, reserve and alignment padding
+        let mut data = AccessRegistry::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(admin.as_ref());
+        data.push(254);
+        for count in [1u32, 2, 3, 4] {
+            data.extend_from_slice(&count.to_le_bytes());
+        }
+        data.extend_from_slice(&[0; 32 + 3]);
+        data.resize(8 + AccessRegistry::SIZE, 0);
+
+        let registry = AccessRegistry::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(registry.admin, admin);
+        assert_eq!(registry.bump, [254]);
+        assert_eq!(registry.speakers, 1);
+        assert_eq!(registry.proposal_managers, 4);
+        assert!(registry.threshold == Threshold::Majority);
+        assert!(!registry.role_governance);
+        assert!(registry.pending_admin.is_none());
+        assert!(registry.admin_signers.is_empty());
+        assert_eq!(registry.guardian_weight, 0);
+    }
+
+    #[test]
+    fn legacy_access_decodes_after_realloc() {
+        let access_registry = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        // discriminator, access registry, role, owner
+        let mut data = Access::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(access_registry.as_ref());
+        data.push(Role::Guardian as u8);
+        data.extend_from_slice(owner.as_ref());
+        assert!(Access::try_deserialize(&mut &data[..]).is_err());
+
+        data.resize(8 + Access::SIZE, 0);
+        let access = Access::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(access.access_registry, access_registry);
+        assert!(access.role == Role::Guardian);
+        assert_eq!(access.owner, owner);
+        assert!(access.expires_at.is_none());
+        assert!(access.delegate.is_none());
+    }
+}
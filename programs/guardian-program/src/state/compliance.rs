@@ -1,30 +1,62 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::clock::UnixTimestamp};
 
 use super::proposal::Proposal;
 
+pub const MAX_RISK_SCORE: u8 = 100;
+
+// compliance categories, combined as bit flags
+pub const CATEGORY_SANCTIONED: u8 = 1 << 0;
+pub const CATEGORY_MIXER: u8 = 1 << 1;
+pub const CATEGORY_EXPLOIT: u8 = 1 << 2;
+pub const CATEGORY_SCAM: u8 = 1 << 3;
+pub const CATEGORY_COUNT: usize = u8::BITS as usize;
+
+/// Variant order keeps the Borsh encoding of the former `is_whitelist` bool.
+#[derive(Clone, Copy, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub enum ComplianceStatus {
+    Blacklisted,
+    Whitelisted,
+    /// Allowed, but flagged for monitoring or rate limits.
+    Watchlisted,
+}
+
 #[account]
 pub struct ComplianceRegistry {
     pub access_registry: Pubkey,
     pub whitelists: u64,
     pub blacklists: u64,
+    // number of entries per category bit
+    // appended after the original layout, older registries need `migrate_compliance_registry`
+    pub categories: [u64; CATEGORY_COUNT],
+    pub watchlists: u64,
 }
 
 impl ComplianceRegistry {
     pub const SIZE: usize = std::mem::size_of::<Self>();
     
-    pub(crate) fn register(&mut self, is_whitelist: bool) {
-        if is_whitelist {
-            self.whitelists += 1;
-        } else {
-            self.blacklists += 1;
+    pub(crate) fn register(&mut self, status: ComplianceStatus, categories: u8) {
+        match status {
+            ComplianceStatus::Blacklisted => { self.blacklists += 1; },
+            ComplianceStatus::Whitelisted => { self.whitelists += 1; },
+            ComplianceStatus::Watchlisted => { self.watchlists += 1; },
+        }
+        for (i, count) in self.categories.iter_mut().enumerate() {
+            if categories & (1 << i) != 0 {
+                *count += 1;
+            }
         }
     }
     
-    pub(crate) fn revoke(&mut self, is_whitelist: bool) {
-        if is_whitelist {
-            self.whitelists -= 1;
-        } else {
-            self.blacklists -= 1;
+    pub(crate) fn revoke(&mut self, status: ComplianceStatus, categories: u8) {
+        match status {
+            ComplianceStatus::Blacklisted => { self.blacklists -= 1; },
+            ComplianceStatus::Whitelisted => { self.whitelists -= 1; },
+            ComplianceStatus::Watchlisted => { self.watchlists -= 1; },
+        }
+        for (i, count) in self.categories.iter_mut().enumerate() {
+            if categories & (1 << i) != 0 {
+                *count -= 1;
+            }
         }
     }
 }
@@ -35,12 +67,20 @@ pub struct Compliance {
     pub payer: Pubkey,
     pub proposal: Pubkey,
     pub speaker: Pubkey,
-    pub is_whitelist: bool,
+    pub status: ComplianceStatus,
     pub description: String,
+    // appended after the fixed size description so existing entries decode as permanent,
+    // entries whose description left less than 4 bytes free need `migrate_compliance` first
+    pub valid_until: Option<UnixTimestamp>,
+    // 0 to `MAX_RISK_SCORE`
+    pub risk_score: u8,
+    pub categories: u8,
+    // proposal which wrote the entry before `proposal`
+    pub previous_proposal: Option<Pubkey>,
 }
 
 impl Compliance {
-    pub const SIZE: usize = 1 + 32 + 32 + 32 + 1 + 256;
+    pub const SIZE: usize = 1 + 32 + 32 + 32 + 1 + 256 + 9 + 1 + 1 + 33;
 
     pub(crate) fn new(
         bump: u8,
@@ -54,7 +94,121 @@ impl Compliance {
             proposal: proposal_key,
             speaker: proposal.speaker,
             description: proposal.description.clone(),
-            is_whitelist: proposal.is_whitelist,
+            status: proposal.compliance_status,
+            valid_until: proposal.valid_until,
+            risk_score: proposal.risk_score,
+            categories: proposal.categories,
+            previous_proposal: None,
+        }
+    }
+
+    /// Rewrites the entry from a later proposal, keeping the original payer.
+    pub(crate) fn update(&mut self, proposal_key: Pubkey, proposal: &Proposal) {
+        self.previous_proposal = Some(self.proposal);
+        self.proposal = proposal_key;
+        self.speaker = proposal.speaker;
+        self.status = proposal.compliance_status;
+        self.description = proposal.description.clone();
+        self.valid_until = proposal.valid_until;
+        self.risk_score = proposal.risk_score;
+        self.categories = proposal.categories;
+    }
+
+    /// Blacklisted entries count as the highest risk, including the ones written before risk
+    /// scores existed, which decode with a zero score.
+    pub fn effective_risk_score(&self) -> u8 {
+        match self.status {
+            ComplianceStatus::Blacklisted => MAX_RISK_SCORE,
+            ComplianceStatus::Whitelisted | ComplianceStatus::Watchlisted => self.risk_score,
+        }
+    }
+
+    pub fn is_valid(&self, now: UnixTimestamp) -> bool {
+        match self.valid_until {
+            Some(valid_until) => now <= valid_until,
+            None => true,
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    #[test]
+    fn status_decodes_former_is_whitelist() {
+        let status = ComplianceStatus::try_from_slice(&false.try_to_vec().unwrap()).unwrap();
+        assert!(status == ComplianceStatus::Blacklisted);
+        let status = ComplianceStatus::try_from_slice(&true.try_to_vec().unwrap()).unwrap();
+        assert!(status == ComplianceStatus::Whitelisted);
+    }
+
+    #[test]
+    fn legacy_registry_decodes_after_realloc() {
+        let access_registry = Pubkey::new_unique();
+        // discriminator, access registry, whitelists, blacklists
+        let mut data = ComplianceRegistry::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(access_registry.as_ref());
+        data.extend_from_slice(&3u64.to_le_bytes());
+        data.extend_from_slice(&5u64.to_le_bytes());
+        assert!(ComplianceRegistry::try_deserialize(&mut &data[..]).is_err());
+
+        data.resize(8 + ComplianceRegistry::SIZE, 0);
+        let mut registry = ComplianceRegistry::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(registry.access_registry, access_registry);
+        assert_eq!(registry.whitelists, 3);
+        assert_eq!(registry.blacklists, 5);
+        assert_eq!(registry.watchlists, 0);
+        assert_eq!(registry.categories, [0; CATEGORY_COUNT]);
+
+        registry.register(ComplianceStatus::Watchlisted, CATEGORY_MIXER);
+        assert_eq!(registry.watchlists, 1);
+        assert_eq!(registry.categories[1], 1);
+    }
+
+    #[test]
+    fn blacklisted_entries_have_highest_risk() {
+        let mut compliance = Compliance {
+            bump: 255,
+            payer: Pubkey::new_unique(),
+            proposal: Pubkey::new_unique(),
+            speaker: Pubkey::new_unique(),
+            status: ComplianceStatus::Blacklisted,
+            description: String::new(),
+            valid_until: None,
+            risk_score: 0,
+            categories: 0,
+            previous_proposal: None,
+        };
+        assert_eq!(compliance.effective_risk_score(), MAX_RISK_SCORE);
+
+        compliance.status = ComplianceStatus::Watchlisted;
+        compliance.risk_score = 40;
+        assert_eq!(compliance.effective_risk_score(), 40);
+    }
+
+    #[test]
+    fn legacy_entry_with_full_description_decodes_after_realloc() {
+        let payer = Pubkey::new_unique();
+        // discriminator, bump, payer, proposal, speaker, is_whitelist, description
+        let mut data = Compliance::DISCRIMINATOR.to_vec();
+        data.push(255);
+        data.extend_from_slice(payer.as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&true.try_to_vec().unwrap());
+        data.extend_from_slice(&"a".repeat(252).try_to_vec().unwrap());
+        // the original layout reserved 256 bytes for the description
+        assert_eq!(data.len(), 8 + 1 + 32 + 32 + 32 + 1 + 256);
+        assert!(Compliance::try_deserialize(&mut &data[..]).is_err());
+
+        data.resize(8 + Compliance::SIZE, 0);
+        let compliance = Compliance::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(compliance.payer, payer);
+        assert!(compliance.status == ComplianceStatus::Whitelisted);
+        assert_eq!(compliance.description.len(), 252);
+        assert!(compliance.valid_until.is_none());
+        assert_eq!(compliance.risk_score, 0);
+        assert!(compliance.previous_proposal.is_none());
+    }
+}
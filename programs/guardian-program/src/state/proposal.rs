@@ -2,14 +2,22 @@ use anchor_lang::{prelude::*, solana_program::clock::UnixTimestamp};
 
 use crate::error::ZktGuardianError;
 
-const EXPIRE_DAYS: i64 = 7 * 3600 * 24;
+use super::{
+    access::{AccessRegistry, Role, Threshold},
+    compliance::{ComplianceStatus, MAX_RISK_SCORE},
+};
+
+pub const DEFAULT_VOTING_PERIOD: i64 = 7 * 3600 * 24;
+pub const DEFAULT_EXECUTION_PERIOD: i64 = 30 * 3600 * 24;
 const MAX_TARGET_ACCOUNTS: usize = 20;
+const MAX_VETO_REASON_LEN: usize = 128;
 
 #[derive(Clone, Copy, PartialEq, AnchorSerialize, AnchorDeserialize)]
 pub enum ProposalStatus {
     Pending,
     Approved,
     Rejected,
+    Expired,
 }
 
 impl std::fmt::Display for ProposalStatus {
@@ -18,38 +26,146 @@ impl std::fmt::Display for ProposalStatus {
             Self::Pending => "pending",
             Self::Approved => "approved",
             Self::Rejected => "rejected",
+            Self::Expired => "expired",
         };
         write!(f, "{}", std)
     }
 }
 
+#[derive(Clone, Copy, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub enum ProposalKind {
+    /// Writes a compliance entry with `compliance_status` for every target account.
+    Register,
+    /// Closes the compliance entries of the target accounts, `compliance_status` is ignored.
+    Revoke,
+    /// Assigns `role` to every target account, `compliance_status` is ignored.
+    AssignRole { role: Role },
+    /// Revokes `role` from every target account, `compliance_status` is ignored.
+    RevokeRole { role: Role },
+    /// Replaces the validity of the existing compliance entries of the target accounts with
+    /// the proposal's `valid_until`, `compliance_status` is ignored.
+    Renew,
+    /// Sets the weight of every target guardian, `compliance_status` is ignored.
+    SetGuardianWeight { weight: u32 },
+}
+
+impl ProposalKind {
+    /// Number of remaining accounts the execution expects for every target account.
+    pub(crate) fn accounts_per_target(&self) -> usize {
+        match self {
+            // compliance
+            Self::Register | Self::Renew => 1,
+            // compliance, compliance payer
+            Self::Revoke => 2,
+            // access
+            Self::AssignRole { .. } | Self::SetGuardianWeight { .. } => 1,
+            // access, access payer
+            Self::RevokeRole { .. } => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub enum VoteChoice {
+    Approve,
+    Reject,
+    Abstain,
+}
+
 #[account]
 pub struct ProposalRegistry {
     pub access_registry: Pubkey,
     pub pending: u64,
     pub approved: u64,
     pub rejected: u64,
+    // appended after the original layout, older registries need `migrate_proposal_registry`
+    pub cancelled: u64,
+    pub expired: u64,
+    pub voting_period: i64,
+    // time between approval and execution
+    pub execution_delay: i64,
+    // blacklist registrations skip the execution delay when set
+    pub blacklist_exempt: bool,
+    // time an approved proposal can be executed once the execution delay has passed
+    pub execution_period: i64,
+    // id of the next proposal, proposals are seeded by the registry and their id
+    pub next_proposal_id: u64,
 }
 
 impl ProposalRegistry {
     pub const SIZE: usize = std::mem::size_of::<Self>();
+
+    /// Fills the periods appended after the original layout, whose zeroed values would let
+    /// every new proposal expire at once.
+    pub(crate) fn migrate(&mut self) {
+        self.voting_period = DEFAULT_VOTING_PERIOD;
+        self.execution_period = DEFAULT_EXECUTION_PERIOD;
+    }
+
+    pub(crate) fn set_voting_period(&mut self, voting_period: i64) -> Result<()> {
+        require_gt!(voting_period, 0, ZktGuardianError::InvalidVotingPeriod);
+        self.voting_period = voting_period;
+
+        Ok(())
+    }
+
+    pub(crate) fn set_execution_delay(&mut self, execution_delay: i64, blacklist_exempt: bool) -> Result<()> {
+        require_gte!(execution_delay, 0, ZktGuardianError::InvalidExecutionDelay);
+        self.execution_delay = execution_delay;
+        self.blacklist_exempt = blacklist_exempt;
+
+        Ok(())
+    }
+
+    pub(crate) fn set_execution_period(&mut self, execution_period: i64) -> Result<()> {
+        require_gt!(execution_period, 0, ZktGuardianError::InvalidExecutionPeriod);
+        self.execution_period = execution_period;
+
+        Ok(())
+    }
 }
 
 #[account]
 pub struct Proposal {
     pub proposal_registry: Pubkey,
+    pub id: u64,
     pub speaker: Pubkey,
     pub target_accounts: Vec<Pubkey>,
-    pub is_whitelist: bool,
+    pub kind: ProposalKind,
+    pub compliance_status: ComplianceStatus,
+    // validity of the compliance entries written or renewed by this proposal
+    pub valid_until: Option<UnixTimestamp>,
+    pub risk_score: u8,
+    pub categories: u8,
     pub description: String,
     pub timestamp: UnixTimestamp,
+    pub deadline: UnixTimestamp,
     pub status: ProposalStatus,
-    pub voters: u32,
+    pub approved_at: Option<UnixTimestamp>,
+    // execution window, fixed at approval like `deadline` is fixed at creation
+    pub executable_at: Option<UnixTimestamp>,
+    pub execution_deadline: Option<UnixTimestamp>,
+    // accumulated guardian weight per vote choice
+    pub approvals: u64,
+    pub rejections: u64,
+    pub abstentions: u64,
     pub guardians: u32,
+    pub guardian_weight: u64,
+    pub guardian_epoch: u64,
+    pub quorum: u32,
+    pub threshold: Threshold,
+    pub min_community_support: u32,
+    pub community_support: u32,
+    pub community_opposition: u32,
+    pub proposal_targets: Option<Pubkey>,
+    pub target_count: u32,
+    pub executed: u32,
+    // set once a vetoer rejected the proposal
+    pub veto_reason: Option<String>,
 }
 
 impl Proposal {
-    pub const SIZE: usize = 32 + 32 + MAX_TARGET_ACCOUNTS * 32 + 1 + 256 + 8 + 1 + 4 + 4;
+    pub const SIZE: usize = 32 + 8 + 32 + MAX_TARGET_ACCOUNTS * 32 + 5 + 1 + 9 + 1 + 1 + 256 + 8 + 8 + 1 + 9 + 9 + 9 + 8 + 8 + 8 + 4 + 8 + 8 + 4 + 5 + 4 + 4 + 4 + 33 + 4 + 4 + 1 + 4 + MAX_VETO_REASON_LEN;
 
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
@@ -57,42 +173,160 @@ impl Proposal {
         registry: &mut ProposalRegistry,
         speaker: Pubkey,
         target_accounts: Vec<Pubkey>,
-        is_whitelist: bool,
+        kind: ProposalKind,
+        compliance_status: ComplianceStatus,
+        valid_until: Option<UnixTimestamp>,
+        risk_score: u8,
+        categories: u8,
         description: String,
         clock: &Clock,
-        guardians: u32,
+        access_registry: &AccessRegistry,
     ) -> Result<Self> {
         require_gte!(
             MAX_TARGET_ACCOUNTS,
             target_accounts.len(),
             ZktGuardianError::TooManyTargetAccounts,
         );
+        if let Some(valid_until) = valid_until {
+            require_gt!(valid_until, clock.unix_timestamp, ZktGuardianError::InvalidValidUntil);
+        }
+        require_gte!(MAX_RISK_SCORE, risk_score, ZktGuardianError::InvalidRiskScore);
+        // role changes only go through proposals once the admin handed them over
+        if let ProposalKind::AssignRole { .. }
+            | ProposalKind::RevokeRole { .. }
+            | ProposalKind::SetGuardianWeight { .. } = kind
+        {
+            require!(access_registry.role_governance, ZktGuardianError::RoleGovernanceDisabled);
+        }
+        if let ProposalKind::SetGuardianWeight { weight } = kind {
+            require_gt!(weight, 0, ZktGuardianError::InvalidGuardianWeight);
+        }
+        let id = registry.next_proposal_id;
+        registry.next_proposal_id += 1;
         registry.pending += 1;
+        let target_count = target_accounts.len() as u32;
         
         Ok(Self {
             proposal_registry: registry_key,
+            id,
             speaker,
             target_accounts,
-            is_whitelist,
+            kind,
+            compliance_status,
+            valid_until,
+            risk_score,
+            categories,
             description,
             timestamp: clock.unix_timestamp,
+            deadline: clock.unix_timestamp + registry.voting_period,
             status: ProposalStatus::Pending,
-            voters: 0,
-            guardians,
+            approved_at: None,
+            executable_at: None,
+            execution_deadline: None,
+            approvals: 0,
+            rejections: 0,
+            abstentions: 0,
+            guardians: access_registry.guardians,
+            guardian_weight: access_registry.guardian_weight,
+            guardian_epoch: access_registry.guardian_epoch,
+            quorum: access_registry.quorum,
+            threshold: access_registry.threshold,
+            min_community_support: access_registry.min_community_support,
+            community_support: 0,
+            community_opposition: 0,
+            proposal_targets: None,
+            target_count,
+            executed: 0,
+            veto_reason: None,
         })
     }
 
-    pub(crate) fn vote(&mut self, registry: &mut ProposalRegistry) -> Result<()> {
+    /// Moves the target accounts of the proposal into a separate, unbounded target list.
+    pub(crate) fn bind_targets(
+        &mut self,
+        proposal_key: Pubkey,
+        targets_key: Pubkey,
+        targets: &mut ProposalTargets,
+    ) -> Result<()> {
+        require!(self.target_accounts.is_empty(), ZktGuardianError::UnmatchedTargetAccountsLength);
+        require_keys_eq!(targets.proposal, Pubkey::default(), ZktGuardianError::ProposalTargetsSealed);
+
+        targets.proposal = proposal_key;
+        self.proposal_targets = Some(targets_key);
+        self.target_count = targets.target_accounts.len() as u32;
+
+        Ok(())
+    }
+
+    pub(crate) fn vote(
+        &mut self,
+        choice: VoteChoice,
+        weight: u64,
+        clock: &Clock,
+        registry: &mut ProposalRegistry,
+    ) -> Result<()> {
+        require_eq!(self.status, ProposalStatus::Pending, ZktGuardianError::OnlyPendingProposal);
+        require_gte!(self.deadline, clock.unix_timestamp, ZktGuardianError::ProposalExpired);
+
+        match choice {
+            VoteChoice::Approve => { self.approvals += weight; },
+            VoteChoice::Reject => { self.rejections += weight; },
+            VoteChoice::Abstain => { self.abstentions += weight; },
+        }
+        self.tally(clock, registry);
+        
+        Ok(())
+    }
+
+    /// Records an advisory community vote, which only counts towards the minimum community
+    /// support required before the guardians' approval takes effect.
+    pub(crate) fn signal(
+        &mut self,
+        support: bool,
+        clock: &Clock,
+        registry: &mut ProposalRegistry,
+    ) -> Result<()> {
         require_eq!(self.status, ProposalStatus::Pending, ZktGuardianError::OnlyPendingProposal);
+        require_gte!(self.deadline, clock.unix_timestamp, ZktGuardianError::ProposalExpired);
+
+        if support {
+            self.community_support += 1;
+        } else {
+            self.community_opposition += 1;
+        }
+        self.tally(clock, registry);
+
+        Ok(())
+    }
 
-        self.voters += 1;
-        if self.voters * 2 > self.guardians {
+    fn tally(&mut self, clock: &Clock, registry: &mut ProposalRegistry) {
+        let votes = self.approvals + self.rejections + self.abstentions;
+        // a ratio of an empty guardian set is always reached, so some approval is required
+        if votes >= self.quorum as u64
+            && self.approvals > 0
+            && self.threshold.is_reached(self.approvals, self.guardian_weight)
+            && self.community_support >= self.min_community_support
+        {
             self.status = ProposalStatus::Approved;
+            // blacklist registrations can skip the delay so emergency blocks apply at once
+            let executable_at = if registry.blacklist_exempt
+                && self.kind == ProposalKind::Register
+                && self.compliance_status == ComplianceStatus::Blacklisted
+            {
+                clock.unix_timestamp
+            } else {
+                clock.unix_timestamp + registry.execution_delay
+            };
+            self.approved_at = Some(clock.unix_timestamp);
+            self.executable_at = Some(executable_at);
+            self.execution_deadline = Some(executable_at + registry.execution_period);
             registry.pending -= 1;
             registry.approved += 1;
+        } else if self.rejections * 2 > self.guardian_weight {
+            self.status = ProposalStatus::Rejected;
+            registry.pending -= 1;
+            registry.rejected += 1;
         }
-        
-        Ok(())
     }
 
     pub(crate) fn reject_expired(
@@ -101,14 +335,457 @@ impl Proposal {
         registry: &mut ProposalRegistry,
     ) -> Result<()> {
         require_eq!(self.status, ProposalStatus::Pending, ZktGuardianError::OnlyPendingProposal);
-        require_gte!(
-            self.timestamp + EXPIRE_DAYS,
+        require_gt!(
             clock.unix_timestamp,
+            self.deadline,
             ZktGuardianError::ProposalNotExpired,
         );
+        self.status = ProposalStatus::Rejected;
         registry.pending -= 1;
         registry.rejected += 1;
         
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Rejects a pending proposal, or an approved one whose execution has not started yet, so
+    /// a veto never leaves the target accounts partly written.
+    pub(crate) fn veto(&mut self, reason: String, registry: &mut ProposalRegistry) -> Result<()> {
+        require_gte!(MAX_VETO_REASON_LEN, reason.len(), ZktGuardianError::VetoReasonTooLong);
+        match self.status {
+            ProposalStatus::Pending => { registry.pending -= 1; },
+            ProposalStatus::Approved => {
+                require_eq!(self.executed, 0, ZktGuardianError::ProposalAlreadyExecuted);
+                registry.approved -= 1;
+            },
+            ProposalStatus::Rejected | ProposalStatus::Expired => {
+                return err!(ZktGuardianError::ProposalNotVetoable);
+            },
+        }
+        self.status = ProposalStatus::Rejected;
+        self.veto_reason = Some(reason);
+        registry.rejected += 1;
+
+        Ok(())
+    }
+
+    /// Whether the execution delay fixed at approval has passed.
+    pub fn is_executable(&self, now: UnixTimestamp) -> bool {
+        match self.executable_at {
+            Some(executable_at) => now >= executable_at,
+            None => false,
+        }
+    }
+
+    /// Whether the execution deadline passed before any target was executed. Once execution
+    /// started the remaining targets stay executable so the proposal can't be left half applied.
+    pub fn is_execution_expired(&self, now: UnixTimestamp) -> bool {
+        if self.executed > 0 {
+            return false;
+        }
+        match self.execution_deadline {
+            Some(execution_deadline) => now > execution_deadline,
+            None => false,
+        }
+    }
+
+    /// Moves an approved proposal which was not executed before its execution deadline
+    /// to the terminal expired status.
+    pub(crate) fn expire(&mut self, clock: &Clock, registry: &mut ProposalRegistry) -> Result<()> {
+        require_eq!(self.status, ProposalStatus::Approved, ZktGuardianError::ProposalNotApproved);
+        require!(
+            self.is_execution_expired(clock.unix_timestamp),
+            ZktGuardianError::ProposalNotExpired,
+        );
+        self.status = ProposalStatus::Expired;
+        registry.approved -= 1;
+        registry.expired += 1;
+
+        Ok(())
+    }
+
+    pub(crate) fn is_executed(&self) -> bool {
+        self.executed == self.target_count
+    }
+
+    pub(crate) fn cancel(&mut self, registry: &mut ProposalRegistry) -> Result<()> {
+        require_eq!(self.status, ProposalStatus::Pending, ZktGuardianError::OnlyPendingProposal);
+        registry.pending -= 1;
+        registry.cancelled += 1;
+
+        Ok(())
+    }
+}
+
+#[account]
+pub struct ProposalTargets {
+    pub speaker: Pubkey,
+    /// Default until the list is bound to a proposal, after which it is sealed.
+    pub proposal: Pubkey,
+    pub target_accounts: Vec<Pubkey>,
+}
+
+impl ProposalTargets {
+    pub const fn space(len: usize) -> usize {
+        32 + 32 + 4 + len * 32
+    }
+}
+
+#[account]
+pub struct VoteRecord {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub guardian: Pubkey,
+    // the guardian itself, or its delegate when the vote was delegated
+    pub payer: Pubkey,
+    pub choice: VoteChoice,
+}
+
+impl VoteRecord {
+    pub const SIZE: usize = std::mem::size_of::<Self>();
+
+    pub(crate) fn new(
+        bump: u8,
+        proposal: Pubkey,
+        guardian: Pubkey,
+        payer: Pubkey,
+        choice: VoteChoice,
+    ) -> Self {
+        Self {
+            bump,
+            proposal,
+            guardian,
+            payer,
+            choice,
+        }
+    }
+}
+
+#[account]
+pub struct SignalRecord {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub support: bool,
+}
+
+impl SignalRecord {
+    pub const SIZE: usize = std::mem::size_of::<Self>();
+
+    pub(crate) fn new(bump: u8, proposal: Pubkey, voter: Pubkey, support: bool) -> Self {
+        Self {
+            bump,
+            proposal,
+            voter,
+            support,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+    use crate::state::CATEGORY_EXPLOIT;
+
+    const CREATED_AT: UnixTimestamp = 1_700_000_000;
+
+    fn clock_at(unix_timestamp: UnixTimestamp) -> Clock {
+        Clock {
+            unix_timestamp,
+            ..Clock::default()
+        }
+    }
+
+    fn access_registry() -> AccessRegistry {
+        AccessRegistry {
+            admin: Pubkey::new_unique(),
+            pending_admin: None,
+            admin_threshold: 0,
+            admin_signers: vec![],
+            bump: [255],
+            speakers: 1,
+            voters: 0,
+            guardians: 3,
+            proposal_managers: 0,
+            guardian_weight: 3,
+            quorum: 0,
+            threshold: Threshold::Majority,
+            role_governance: false,
+            guardian_epoch: 0,
+            min_community_support: 0,
+            vetoers: 0,
+            _reserve: [0; 6],
+        }
+    }
+
+    fn new_proposal(
+        registry: &mut ProposalRegistry,
+        kind: ProposalKind,
+        access_registry: &AccessRegistry,
+    ) -> Result<Proposal> {
+        Proposal::new(
+            Pubkey::new_unique(),
+            registry,
+            Pubkey::new_unique(),
+            vec![Pubkey::new_unique()],
+            kind,
+            ComplianceStatus::Blacklisted,
+            None,
+            MAX_RISK_SCORE,
+            CATEGORY_EXPLOIT,
+            "exploit".to_string(),
+            &clock_at(CREATED_AT),
+            access_registry,
+        )
+    }
+
+    fn setup() -> (ProposalRegistry, Proposal) {
+        let mut registry = ProposalRegistry {
+            access_registry: Pubkey::new_unique(),
+            pending: 0,
+            approved: 0,
+            rejected: 0,
+            cancelled: 0,
+            expired: 0,
+            voting_period: DEFAULT_VOTING_PERIOD,
+            execution_delay: 0,
+            blacklist_exempt: false,
+            execution_period: DEFAULT_EXECUTION_PERIOD,
+            next_proposal_id: 0,
+        };
+        let proposal = new_proposal(&mut registry, ProposalKind::Register, &access_registry()).unwrap();
+
+        (registry, proposal)
+    }
+
+    #[test]
+    fn role_proposals_require_role_governance() {
+        let (mut registry, _) = setup();
+        let mut access_registry = access_registry();
+        let kinds = [
+            ProposalKind::AssignRole { role: Role::Guardian },
+            ProposalKind::RevokeRole { role: Role::Guardian },
+            ProposalKind::SetGuardianWeight { weight: 2 },
+        ];
+
+        for kind in kinds {
+            let err = new_proposal(&mut registry, kind, &access_registry).err().unwrap();
+            assert_eq!(err, ZktGuardianError::RoleGovernanceDisabled.into());
+        }
+        assert_eq!(registry.pending, 1);
+
+        access_registry.role_governance = true;
+        for kind in kinds {
+            new_proposal(&mut registry, kind, &access_registry).unwrap();
+        }
+        assert_eq!(registry.pending, 4);
+    }
+
+    #[test]
+    fn legacy_registry_decodes_after_realloc() {
+        let access_registry = Pubkey::new_unique();
+        // discriminator, access registry, pending, approved, rejected
+        let mut data = ProposalRegistry::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(access_registry.as_ref());
+        for count in [1u64, 2, 3] {
+            data.extend_from_slice(&count.to_le_bytes());
+        }
+        assert!(ProposalRegistry::try_deserialize(&mut &data[..]).is_err());
+
+        data.resize(8 + ProposalRegistry::SIZE, 0);
+        let mut registry = ProposalRegistry::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(registry.access_registry, access_registry);
+        assert_eq!(registry.pending, 1);
+        assert_eq!(registry.rejected, 3);
+        assert_eq!(registry.cancelled, 0);
+        assert_eq!(registry.next_proposal_id, 0);
+
+        registry.migrate();
+        assert_eq!(registry.voting_period, DEFAULT_VOTING_PERIOD);
+        assert_eq!(registry.execution_delay, 0);
+        assert_eq!(registry.execution_period, DEFAULT_EXECUTION_PERIOD);
+    }
+
+    #[test]
+    fn reject_expired_fails_before_deadline() {
+        let (mut registry, mut proposal) = setup();
+
+        for now in [CREATED_AT, proposal.deadline - 1, proposal.deadline] {
+            let err = proposal.reject_expired(&clock_at(now), &mut registry).unwrap_err();
+            assert_eq!(err, ZktGuardianError::ProposalNotExpired.into());
+        }
+        assert!(proposal.status == ProposalStatus::Pending);
+        assert_eq!(registry.pending, 1);
+        assert_eq!(registry.rejected, 0);
+    }
+
+    #[test]
+    fn reject_expired_after_deadline() {
+        let (mut registry, mut proposal) = setup();
+
+        proposal.reject_expired(&clock_at(proposal.deadline + 1), &mut registry).unwrap();
+        assert!(proposal.status == ProposalStatus::Rejected);
+        assert_eq!(registry.pending, 0);
+        assert_eq!(registry.rejected, 1);
+
+        let err = proposal.reject_expired(&clock_at(proposal.deadline + 2), &mut registry).unwrap_err();
+        assert_eq!(err, ZktGuardianError::OnlyPendingProposal.into());
+        assert_eq!(registry.rejected, 1);
+    }
+
+    #[test]
+    fn reject_expired_keeps_deadline_snapshot() {
+        let (mut registry, mut proposal) = setup();
+        registry.set_voting_period(3600).unwrap();
+
+        let err = proposal.reject_expired(&clock_at(CREATED_AT + 3601), &mut registry).unwrap_err();
+        assert_eq!(err, ZktGuardianError::ProposalNotExpired.into());
+    }
+
+    #[test]
+    fn vote_fails_after_deadline() {
+        let (mut registry, mut proposal) = setup();
+
+        proposal.vote(VoteChoice::Abstain, 1, &clock_at(proposal.deadline), &mut registry).unwrap();
+        let err = proposal
+            .vote(VoteChoice::Approve, 1, &clock_at(proposal.deadline + 1), &mut registry)
+            .unwrap_err();
+        assert_eq!(err, ZktGuardianError::ProposalExpired.into());
+        assert_eq!(proposal.approvals, 0);
+    }
+
+    #[test]
+    fn approval_waits_for_community_support() {
+        let (mut registry, mut proposal) = setup();
+        proposal.min_community_support = 1;
+        let clock = clock_at(CREATED_AT);
+
+        proposal.vote(VoteChoice::Approve, 1, &clock, &mut registry).unwrap();
+        proposal.vote(VoteChoice::Approve, 1, &clock, &mut registry).unwrap();
+        assert!(proposal.status == ProposalStatus::Pending);
+
+        proposal.signal(false, &clock, &mut registry).unwrap();
+        assert!(proposal.status == ProposalStatus::Pending);
+
+        proposal.signal(true, &clock, &mut registry).unwrap();
+        assert!(proposal.status == ProposalStatus::Approved);
+        assert_eq!(registry.approved, 1);
+    }
+
+    #[test]
+    fn signal_cannot_approve_without_guardian_approval() {
+        let (mut registry, mut proposal) = setup();
+        proposal.guardian_weight = 0;
+        proposal.threshold = Threshold::Ratio { numerator: 1, denominator: 2 };
+
+        proposal.signal(true, &clock_at(CREATED_AT), &mut registry).unwrap();
+        assert!(proposal.status == ProposalStatus::Pending);
+    }
+
+    #[test]
+    fn veto_fails_once_execution_started() {
+        let (mut registry, mut proposal) = setup();
+        proposal.vote(VoteChoice::Approve, 2, &clock_at(CREATED_AT), &mut registry).unwrap();
+        proposal.target_count = 2;
+        proposal.executed = 1;
+
+        let err = proposal.veto("exploit".to_string(), &mut registry).unwrap_err();
+        assert_eq!(err, ZktGuardianError::ProposalAlreadyExecuted.into());
+        assert!(proposal.status == ProposalStatus::Approved);
+
+        proposal.executed = 0;
+        proposal.veto("exploit".to_string(), &mut registry).unwrap();
+        assert!(proposal.status == ProposalStatus::Rejected);
+        assert_eq!(registry.rejected, 1);
+    }
+
+    #[test]
+    fn approval_waits_for_quorum() {
+        let (mut registry, mut proposal) = setup();
+        proposal.quorum = 3;
+        let clock = clock_at(CREATED_AT);
+
+        proposal.vote(VoteChoice::Approve, 2, &clock, &mut registry).unwrap();
+        assert!(proposal.status == ProposalStatus::Pending);
+
+        proposal.vote(VoteChoice::Abstain, 1, &clock, &mut registry).unwrap();
+        assert!(proposal.status == ProposalStatus::Approved);
+    }
+
+    #[test]
+    fn approval_compares_accumulated_weight() {
+        let (mut registry, mut proposal) = setup();
+        proposal.guardian_weight = 10;
+        let clock = clock_at(CREATED_AT);
+
+        proposal.vote(VoteChoice::Approve, 5, &clock, &mut registry).unwrap();
+        assert!(proposal.status == ProposalStatus::Pending);
+
+        proposal.vote(VoteChoice::Approve, 1, &clock, &mut registry).unwrap();
+        assert!(proposal.status == ProposalStatus::Approved);
+    }
+
+    #[test]
+    fn execution_waits_for_delay() {
+        let (mut registry, mut proposal) = setup();
+        registry.set_execution_delay(3600, false).unwrap();
+        let approved_at = CREATED_AT + 60;
+
+        assert!(!proposal.is_executable(approved_at + 3600));
+        proposal.vote(VoteChoice::Approve, 2, &clock_at(approved_at), &mut registry).unwrap();
+        assert_eq!(proposal.approved_at, Some(approved_at));
+        assert!(!proposal.is_executable(approved_at + 3599));
+        assert!(proposal.is_executable(approved_at + 3600));
+
+        // later delay changes do not apply to approved proposals
+        registry.set_execution_delay(7200, false).unwrap();
+        assert!(proposal.is_executable(approved_at + 3600));
+    }
+
+    #[test]
+    fn blacklist_skips_delay_when_exempt() {
+        let (mut registry, mut proposal) = setup();
+        registry.set_execution_delay(3600, true).unwrap();
+
+        // the setup proposal registers a blacklist entry
+        proposal.vote(VoteChoice::Approve, 2, &clock_at(CREATED_AT), &mut registry).unwrap();
+        assert!(proposal.is_executable(CREATED_AT));
+        assert_eq!(proposal.execution_deadline, Some(CREATED_AT + DEFAULT_EXECUTION_PERIOD));
+    }
+
+    #[test]
+    fn expire_after_execution_deadline() {
+        let (mut registry, mut proposal) = setup();
+        registry.set_execution_delay(3600, false).unwrap();
+        registry.set_execution_period(600).unwrap();
+
+        let err = proposal.expire(&clock_at(CREATED_AT), &mut registry).unwrap_err();
+        assert_eq!(err, ZktGuardianError::ProposalNotApproved.into());
+
+        proposal.vote(VoteChoice::Approve, 2, &clock_at(CREATED_AT), &mut registry).unwrap();
+        assert_eq!(proposal.execution_deadline, Some(CREATED_AT + 4200));
+        let err = proposal.expire(&clock_at(CREATED_AT + 4200), &mut registry).unwrap_err();
+        assert_eq!(err, ZktGuardianError::ProposalNotExpired.into());
+
+        proposal.expire(&clock_at(CREATED_AT + 4201), &mut registry).unwrap();
+        assert!(proposal.status == ProposalStatus::Expired);
+        assert_eq!(registry.approved, 0);
+        assert_eq!(registry.expired, 1);
+    }
+
+    #[test]
+    fn partially_executed_proposal_does_not_expire() {
+        let (mut registry, mut proposal) = setup();
+        registry.set_execution_period(600).unwrap();
+        proposal.vote(VoteChoice::Approve, 2, &clock_at(CREATED_AT), &mut registry).unwrap();
+        proposal.target_count = 2;
+        proposal.executed = 1;
+
+        assert!(!proposal.is_execution_expired(CREATED_AT + 601));
+        let err = proposal.expire(&clock_at(CREATED_AT + 601), &mut registry).unwrap_err();
+        assert_eq!(err, ZktGuardianError::ProposalNotExpired.into());
+        assert!(proposal.status == ProposalStatus::Approved);
+        assert_eq!(registry.approved, 1);
+        assert_eq!(registry.expired, 0);
+    }
+}
@@ -1,13 +1,15 @@
 use anchor_lang::prelude::*;
 use solana_program::pubkey;
 
+use crate::state::ComplianceStatus;
+
 type Compliance<'info> = Account<'info, crate::state::Compliance>;
 
 pub const COMPLIANCE_REGISTRY: Pubkey = pubkey!("EFi3XHwSt8gkMP8DpMdF719YNtZYAqmazcn2gYWCWg7K");
 
 pub fn is_whitelist<'info>(account_info: &'info AccountInfo<'info>) -> bool {
     if let Ok(compliance) = Compliance::try_from(account_info) {
-        compliance.is_whitelist
+        compliance.status == ComplianceStatus::Whitelisted && is_valid(&compliance)
     } else {
         false
     }
@@ -15,8 +17,42 @@ pub fn is_whitelist<'info>(account_info: &'info AccountInfo<'info>) -> bool {
 
 pub fn is_blacklist<'info>(account_info: &'info AccountInfo<'info>) -> bool {
     if let Ok(compliance) = Compliance::try_from(account_info) {
-        !compliance.is_whitelist
+        compliance.status == ComplianceStatus::Blacklisted && is_valid(&compliance)
+    } else {
+        false
+    }
+}
+
+pub fn is_watchlist<'info>(account_info: &'info AccountInfo<'info>) -> bool {
+    if let Ok(compliance) = Compliance::try_from(account_info) {
+        compliance.status == ComplianceStatus::Watchlisted && is_valid(&compliance)
+    } else {
+        false
+    }
+}
+
+/// Accounts without a valid entry carry no known risk.
+pub fn is_risk_below<'info>(account_info: &'info AccountInfo<'info>, risk_score: u8) -> bool {
+    if let Ok(compliance) = Compliance::try_from(account_info) {
+        compliance.effective_risk_score() < risk_score || !is_valid(&compliance)
+    } else {
+        true
+    }
+}
+
+/// Whether the entry carries any of the `categories` bit flags.
+pub fn is_in_category<'info>(account_info: &'info AccountInfo<'info>, categories: u8) -> bool {
+    if let Ok(compliance) = Compliance::try_from(account_info) {
+        compliance.categories & categories != 0 && is_valid(&compliance)
     } else {
         false
     }
-}
\ No newline at end of file
+}
+
+/// Expired entries are treated as absent.
+fn is_valid(compliance: &Compliance) -> bool {
+    match compliance.valid_until {
+        Some(_) => Clock::get().is_ok_and(|clock| compliance.is_valid(clock.unix_timestamp)),
+        None => true,
+    }
+}